@@ -0,0 +1,76 @@
+//! Benchmarks `evm_snapshot`/`evm_revert` against the size of the node state.
+//!
+//! Snapshots share the unchanged state with the live node, so their cost should stay flat as the
+//! state grows, while a deep clone of the same storage grows linearly with it.
+//!
+//! Run with `cargo bench --bench snapshot`.
+use std::collections::HashMap;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use era_test_node::{http_fork_source::HttpForkSource, node::InMemoryNode};
+use zksync_basic_types::{AccountTreeId, H160, H256, U64};
+use zksync_types::StorageKey;
+
+const STATE_SIZES: [u64; 3] = [1_000, 10_000, 100_000];
+const BLOCKS: u64 = 100;
+
+fn storage_key(index: u64) -> StorageKey {
+    StorageKey::new(
+        AccountTreeId::new(H160::from_low_u64_be(index)),
+        H256::zero(),
+    )
+}
+
+/// Creates a node with `keys` written storage slots, spread over [BLOCKS] blocks.
+fn node_with_state(keys: u64) -> InMemoryNode<HttpForkSource> {
+    let node = InMemoryNode::<HttpForkSource>::default();
+    for block in 0..BLOCKS {
+        {
+            let inner = node.get_inner();
+            let mut writer = inner.write().unwrap();
+            for index in (block..keys).step_by(BLOCKS as usize) {
+                writer
+                    .fork_storage
+                    .set_value(storage_key(index), H256::from_low_u64_be(index + 1));
+            }
+        }
+        node.mine_blocks(Some(U64::from(1)), None).unwrap();
+    }
+    node
+}
+
+fn snapshot_and_revert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("snapshot_and_revert");
+    for keys in STATE_SIZES {
+        let node = node_with_state(keys);
+        group.bench_with_input(BenchmarkId::from_parameter(keys), &node, |b, node| {
+            b.iter(|| {
+                let snapshot_id = node.snapshot().unwrap();
+                // modify the state in between, as a test would
+                node.get_inner()
+                    .write()
+                    .unwrap()
+                    .fork_storage
+                    .set_value(storage_key(0), H256::repeat_byte(0xff));
+                node.revert_snapshot(black_box(snapshot_id)).unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn deep_clone_storage(c: &mut Criterion) {
+    let mut group = c.benchmark_group("deep_clone_storage");
+    for keys in STATE_SIZES {
+        let storage = (0..keys)
+            .map(|index| (storage_key(index), H256::from_low_u64_be(index + 1)))
+            .collect::<HashMap<_, _>>();
+        group.bench_with_input(BenchmarkId::from_parameter(keys), &storage, |b, storage| {
+            b.iter(|| black_box(storage.clone()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, snapshot_and_revert, deep_clone_storage);
+criterion_main!(benches);
@@ -10,7 +10,7 @@ use zksync_basic_types::H256;
 use zksync_types::api::{Block, BridgeAddresses, Transaction, TransactionVariant};
 use zksync_types::Transaction as RawTransaction;
 
-use crate::config::cache::CacheConfig;
+use crate::{config::cache::CacheConfig, metrics};
 
 /// Caches full blocks by their hashes
 const CACHE_TYPE_BLOCKS_FULL: &str = "blocks_full";
@@ -100,9 +100,9 @@ impl Cache {
         }
 
         if full_transactions {
-            self.blocks_full.get(hash)
+            metrics::observe_cache_lookup(CACHE_TYPE_BLOCKS_FULL, self.blocks_full.get(hash))
         } else {
-            self.blocks_min.get(hash)
+            metrics::observe_cache_lookup(CACHE_TYPE_BLOCKS_MIN, self.blocks_min.get(hash))
         }
     }
 
@@ -133,7 +133,7 @@ impl Cache {
             return None;
         }
 
-        self.block_hashes.get(number)
+        metrics::observe_cache_lookup("block_hashes", self.block_hashes.get(number))
     }
 
     /// Returns the cached raw transactions for the provided block number.
@@ -142,7 +142,10 @@ impl Cache {
             return None;
         }
 
-        self.block_raw_transactions.get(number)
+        metrics::observe_cache_lookup(
+            CACHE_TYPE_BLOCK_RAW_TRANSACTIONS,
+            self.block_raw_transactions.get(number),
+        )
     }
 
     /// Returns the cached confirmed tokens.
@@ -154,7 +157,10 @@ impl Cache {
         if matches!(self.config, CacheConfig::None) {
             return None;
         }
-        self.confirmed_tokens.get(&(from, limit))
+        metrics::observe_cache_lookup(
+            "confirmed_tokens",
+            self.confirmed_tokens.get(&(from, limit)),
+        )
     }
 
     /// Cache confirmed tokens
@@ -195,7 +201,7 @@ impl Cache {
             return None;
         }
 
-        self.transactions.get(hash)
+        metrics::observe_cache_lookup(CACHE_TYPE_TRANSACTIONS, self.transactions.get(hash))
     }
 
     /// Cache a transaction for the provided hash.
@@ -218,7 +224,7 @@ impl Cache {
             return None;
         }
 
-        self.bridge_addresses.as_ref()
+        metrics::observe_cache_lookup(CACHE_KEY_BRIDGE_ADDRESSES, self.bridge_addresses.as_ref())
     }
 
     /// Cache default bridge addresses.
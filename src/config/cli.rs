@@ -1,3 +1,5 @@
+use std::net::IpAddr;
+
 use clap::{arg, command, Parser, Subcommand, ValueEnum};
 use serde::Deserialize;
 use zksync_types::H256;
@@ -32,10 +34,16 @@ pub struct Cli {
     /// The file path to the config file. If not supplied, defaults will be used.
     pub config: Option<String>,
 
+    #[arg(long)]
+    /// Address to listen on - default: 0.0.0.0
+    pub host: Option<IpAddr>,
     #[arg(long)]
     /// Port to listen on - default: 8011
     pub port: Option<u16>,
     #[arg(long)]
+    /// Port to serve the API over WebSocket on, including `eth_subscribe` - default: disabled
+    pub ws_port: Option<u16>,
+    #[arg(long)]
     /// Show call debug information
     pub show_calls: Option<ShowCalls>,
     #[arg(long)]
@@ -65,6 +73,21 @@ pub struct Cli {
     /// It will make debug log more readable, but will decrease the performance.
     pub resolve_hashes: Option<bool>,
 
+    /// Block time in milliseconds for interval mining. If set, transactions are no longer mined
+    /// as soon as they are submitted, but sealed together with a new block at the given interval.
+    #[arg(long)]
+    pub block_time: Option<u64>,
+
+    /// Maximum gas used by all the transactions in a block. A transaction that would exceed it is
+    /// moved to the next block, while a transaction exceeding it on its own is rejected.
+    #[arg(long)]
+    pub block_gas_limit: Option<u64>,
+
+    /// Keeps the state of every block, so state queries (e.g. `eth_getBalance` or `eth_call`) can target any
+    /// local block. Otherwise only the state of the most recent blocks is kept.
+    #[arg(long)]
+    pub archive: bool,
+
     /// Specifies the option for the system contracts (use compiled built-in with or without signature verification, or load locally).
     /// Default: built-in
     #[arg(long)]
@@ -89,6 +112,55 @@ pub struct Cli {
     /// Cache directory location for `disk` cache - default: ".cache"
     #[arg(long)]
     pub cache_dir: Option<String>,
+
+    /// Loads the node state from the given file on startup, if it exists, and dumps it to the same file on exit.
+    /// Shorthand for `--load-state <PATH> --dump-state <PATH>`.
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["load_state", "dump_state"])]
+    pub state: Option<String>,
+
+    /// Loads the node state from the given file on startup, as previously written by `--dump-state` or `anvil_dumpState`.
+    #[arg(long, value_name = "PATH")]
+    pub load_state: Option<String>,
+
+    /// Dumps the node state to the given file when shut down via SIGINT (Ctrl-C) or SIGTERM.
+    #[arg(long, value_name = "PATH")]
+    pub dump_state: Option<String>,
+
+    /// Directory named snapshots created via `anvil_snapshot` are written to, so they survive restarts.
+    #[arg(long, value_name = "PATH")]
+    pub snapshot_dir: Option<String>,
+
+    /// Also serves the API over IPC, on a Unix socket (or a named pipe on Windows) at the given path.
+    #[arg(long, value_name = "PATH")]
+    pub ipc: Option<String>,
+
+    /// Comma separated origins allowed to make cross-origin requests, `*` allows any origin - default: *
+    #[arg(long, value_delimiter = ',')]
+    pub allow_origin: Option<Vec<String>>,
+
+    /// Maximum size of a request body, in bytes - default: 5 MiB
+    #[arg(long, value_name = "BYTES")]
+    pub max_request_size: Option<usize>,
+
+    /// Serves Prometheus metrics of the node and its API at `/metrics` on the HTTP server.
+    #[arg(long)]
+    pub metrics: bool,
+
+    /// BIP-39 mnemonic the dev accounts are derived from, instead of using the well-known rich wallets.
+    #[arg(long, value_name = "PHRASE")]
+    pub mnemonic: Option<String>,
+
+    /// Number of dev accounts derived from the mnemonic - default: 10
+    #[arg(long, value_name = "NUM", requires = "mnemonic")]
+    pub accounts: Option<usize>,
+
+    /// Balance of each dev account, in ETH - default: 1000000000000
+    #[arg(long, value_name = "ETH")]
+    pub balance: Option<u64>,
+
+    /// BIP-32 derivation path of the dev accounts, the index of each account is appended to it - default: m/44'/60'/0'/0/
+    #[arg(long, value_name = "PATH", requires = "mnemonic")]
+    pub derivation_path: Option<String>,
 }
 
 #[derive(Debug, Subcommand)]
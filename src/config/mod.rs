@@ -1,11 +1,14 @@
 use std::{env, fs::read_to_string, path::PathBuf};
 
+use accounts::AccountsConfig;
 use cache::CacheConfig;
 use cli::{CacheType, Cli, DevSystemContracts};
 use gas::GasConfig;
 use log::LogConfig;
 use node::InMemoryNodeConfig;
 use serde::Deserialize;
+use server::ServerConfig;
+use state::StateConfig;
 
 use crate::system_contracts;
 
@@ -24,6 +27,15 @@ pub struct TestNodeConfig {
     pub log: LogConfig,
     // Caching configuration.
     pub cache: CacheConfig,
+    // State persistence configuration.
+    #[serde(default)]
+    pub state: StateConfig,
+    // API transports configuration.
+    #[serde(default)]
+    pub server: ServerConfig,
+    // Dev accounts configuration.
+    #[serde(default)]
+    pub accounts: AccountsConfig,
 }
 
 impl TestNodeConfig {
@@ -47,13 +59,19 @@ impl TestNodeConfig {
         Ok(config)
     }
 
+    /// Ensures the configuration, after it is loaded and overridden by [`Cli`], holds valid values.
+    pub fn validate(&self) -> eyre::Result<()> {
+        node::validate_block_gas_limit(self.node.block_gas_limit)
+            .map_err(|err| eyre::eyre!(err))?;
+        self.node
+            .seal_criteria
+            .validate()
+            .map_err(|err| eyre::eyre!(err))
+    }
+
     /// Override the config with values provided by [`Cli`].
     pub fn override_with_opts(&mut self, opt: &Cli) {
         // [`NodeConfig`].
-        if let Some(port) = &opt.port {
-            self.node.port = *port;
-        }
-
         if let Some(show_calls) = &opt.show_calls {
             self.node.show_calls = *show_calls;
         }
@@ -72,6 +90,15 @@ impl TestNodeConfig {
         if let Some(resolve_hashes) = &opt.resolve_hashes {
             self.node.resolve_hashes = *resolve_hashes;
         }
+        if let Some(block_time) = &opt.block_time {
+            self.node.block_time = Some(*block_time);
+        }
+        if let Some(block_gas_limit) = &opt.block_gas_limit {
+            self.node.block_gas_limit = *block_gas_limit;
+        }
+        if opt.archive {
+            self.node.archive = true;
+        }
 
         if let Some(contract_options) = &opt.dev_system_contracts {
             self.node.system_contracts_options = match contract_options {
@@ -114,6 +141,58 @@ impl TestNodeConfig {
                 },
             };
         }
+
+        // [`StateConfig`].
+        if let Some(path) = &opt.state {
+            self.state.load_path = Some(path.clone());
+            self.state.dump_path = Some(path.clone());
+        }
+        if let Some(path) = &opt.load_state {
+            self.state.load_path = Some(path.clone());
+        }
+        if let Some(path) = &opt.dump_state {
+            self.state.dump_path = Some(path.clone());
+        }
+        if let Some(dir) = &opt.snapshot_dir {
+            self.state.snapshot_dir = Some(dir.clone());
+        }
+
+        // [`ServerConfig`].
+        if let Some(port) = &opt.port {
+            self.server.port = *port;
+        }
+        if let Some(ws_port) = &opt.ws_port {
+            self.server.ws_port = Some(*ws_port);
+        }
+        if let Some(host) = &opt.host {
+            self.server.host = *host;
+        }
+        if let Some(allow_origin) = &opt.allow_origin {
+            self.server.allow_origin = allow_origin.clone();
+        }
+        if let Some(max_request_size) = &opt.max_request_size {
+            self.server.max_request_size = *max_request_size;
+        }
+        if let Some(path) = &opt.ipc {
+            self.server.ipc_path = Some(path.clone());
+        }
+        if opt.metrics {
+            self.server.metrics = true;
+        }
+
+        // [`AccountsConfig`].
+        if let Some(mnemonic) = &opt.mnemonic {
+            self.accounts.mnemonic = Some(mnemonic.clone());
+        }
+        if let Some(accounts) = &opt.accounts {
+            self.accounts.accounts = *accounts;
+        }
+        if let Some(balance) = &opt.balance {
+            self.accounts.balance = *balance;
+        }
+        if let Some(derivation_path) = &opt.derivation_path {
+            self.accounts.derivation_path = derivation_path.clone();
+        }
     }
 }
 
@@ -122,11 +201,13 @@ pub mod node {
     use serde::Deserialize;
     use std::{fmt::Display, str::FromStr};
 
+    use multivm::vm_latest::constants::BATCH_GAS_LIMIT;
+
+    use super::batch::SealCriteria;
     use crate::system_contracts;
 
     #[derive(Deserialize, Debug, Copy, Clone)]
     pub struct InMemoryNodeConfig {
-        pub port: u16,
         pub show_calls: ShowCalls,
         pub show_outputs: bool,
         pub show_storage_logs: ShowStorageLogs,
@@ -134,12 +215,34 @@ pub mod node {
         pub show_gas_details: ShowGasDetails,
         pub resolve_hashes: bool,
         pub system_contracts_options: system_contracts::Options,
+        /// If set, a new block is mined every `block_time` milliseconds, sealing all the pending transactions.
+        pub block_time: Option<u64>,
+        /// Criteria used to decide when an L1 batch is sealed.
+        #[serde(default)]
+        pub seal_criteria: SealCriteria,
+        /// Maximum gas used by all the transactions in a block.
+        #[serde(default = "default_block_gas_limit")]
+        pub block_gas_limit: u64,
+        /// If true, the state of every block is kept, instead of only the most recent ones.
+        #[serde(default)]
+        pub archive: bool,
+    }
+
+    fn default_block_gas_limit() -> u64 {
+        BATCH_GAS_LIMIT
+    }
+
+    /// Ensures a block gas limit leaves room for at least some gas to be used by the block.
+    pub fn validate_block_gas_limit(block_gas_limit: u64) -> Result<(), String> {
+        if block_gas_limit == 0 {
+            return Err("block gas limit must be greater than 0".to_string());
+        }
+        Ok(())
     }
 
     impl Default for InMemoryNodeConfig {
         fn default() -> Self {
             Self {
-                port: 8011,
                 show_calls: Default::default(),
                 show_outputs: Default::default(),
                 show_storage_logs: Default::default(),
@@ -147,6 +250,10 @@ pub mod node {
                 show_gas_details: Default::default(),
                 resolve_hashes: Default::default(),
                 system_contracts_options: Default::default(),
+                block_time: None,
+                seal_criteria: Default::default(),
+                block_gas_limit: default_block_gas_limit(),
+                archive: false,
             }
         }
     }
@@ -308,6 +415,63 @@ pub mod gas {
     }
 }
 
+pub mod batch {
+    use multivm::vm_latest::constants::{BATCH_GAS_LIMIT, MAX_VM_PUBDATA_PER_BATCH};
+    use serde::{Deserialize, Serialize};
+
+    /// Maximum number of transactions in a batch, matching the default `transaction_slots` of the sequencer.
+    pub const DEFAULT_MAX_TRANSACTIONS_PER_BATCH: usize = 250;
+    /// Maximum size of the transactions' encodings that fit into the bootloader memory of a batch, in bytes.
+    pub const DEFAULT_MAX_BOOTLOADER_MEMORY: usize = 15_000_000;
+
+    /// Criteria used to decide when an L1 batch is sealed, mirroring the ones of the real sequencer.
+    ///
+    /// A transaction that would make the batch exceed any of the limits is moved to the next batch,
+    /// while a transaction that exceeds a limit on its own is rejected.
+    #[derive(Deserialize, Serialize, Debug, Copy, Clone, PartialEq, Eq)]
+    #[serde(default)]
+    pub struct SealCriteria {
+        /// Maximum number of transactions in a batch.
+        pub max_transactions: usize,
+        /// Maximum gas used by all the transactions in a batch.
+        pub max_gas: u64,
+        /// Maximum pubdata published by all the transactions in a batch, in bytes.
+        pub max_pubdata: u64,
+        /// Maximum bootloader memory used by all the transactions in a batch, approximated by the size
+        /// of their encodings, in bytes.
+        pub max_bootloader_memory: usize,
+    }
+
+    impl SealCriteria {
+        /// Ensures every limit leaves room for at least one transaction in a batch.
+        pub fn validate(&self) -> Result<(), String> {
+            let limits = [
+                ("max_transactions", self.max_transactions as u64),
+                ("max_gas", self.max_gas),
+                ("max_pubdata", self.max_pubdata),
+                ("max_bootloader_memory", self.max_bootloader_memory as u64),
+            ];
+            for (name, limit) in limits {
+                if limit == 0 {
+                    return Err(format!("seal criteria {name} must be greater than 0"));
+                }
+            }
+            Ok(())
+        }
+    }
+
+    impl Default for SealCriteria {
+        fn default() -> Self {
+            Self {
+                max_transactions: DEFAULT_MAX_TRANSACTIONS_PER_BATCH,
+                max_gas: BATCH_GAS_LIMIT,
+                max_pubdata: MAX_VM_PUBDATA_PER_BATCH as u64,
+                max_bootloader_memory: DEFAULT_MAX_BOOTLOADER_MEMORY,
+            }
+        }
+    }
+}
+
 pub mod log {
     use serde::Deserialize;
 
@@ -360,3 +524,253 @@ pub mod cache {
         }
     }
 }
+
+pub mod state {
+    use serde::Deserialize;
+
+    /// State persistence configuration.
+    #[derive(Deserialize, Default, Debug, Clone)]
+    #[serde(default)]
+    pub struct StateConfig {
+        /// If set, the node state is loaded from this file on startup, if it exists.
+        pub load_path: Option<String>,
+        /// If set, the node state is dumped to this file on exit.
+        pub dump_path: Option<String>,
+        /// If set, named snapshots are written to this directory, so they survive restarts.
+        pub snapshot_dir: Option<String>,
+    }
+}
+
+pub mod server {
+    use serde::Deserialize;
+    use std::net::{IpAddr, Ipv4Addr};
+
+    /// The default maximum size of a request body, in bytes.
+    pub const DEFAULT_MAX_REQUEST_SIZE: usize = 5 * 1024 * 1024;
+
+    /// API transports configuration.
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(default)]
+    pub struct ServerConfig {
+        /// The port the HTTP server listens on.
+        pub port: u16,
+        /// If set, the API is also served over WebSocket on this port, including `eth_subscribe`.
+        pub ws_port: Option<u16>,
+        /// The address the HTTP and WebSocket servers bind to.
+        pub host: IpAddr,
+        /// The origins allowed to make cross-origin requests over HTTP or to connect over WebSocket, `*` allows
+        /// any origin.
+        pub allow_origin: Vec<String>,
+        /// The maximum size of a request body, in bytes.
+        pub max_request_size: usize,
+        /// If set, the API is also served over IPC, on a Unix socket (or a named pipe on Windows) at this path.
+        pub ipc_path: Option<String>,
+        /// If true, Prometheus metrics are served at `/metrics` on the HTTP server.
+        pub metrics: bool,
+    }
+
+    impl Default for ServerConfig {
+        fn default() -> Self {
+            Self {
+                port: 8011,
+                ws_port: None,
+                host: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+                allow_origin: vec!["*".to_string()],
+                max_request_size: DEFAULT_MAX_REQUEST_SIZE,
+                ipc_path: None,
+                metrics: false,
+            }
+        }
+    }
+}
+
+pub mod accounts {
+    use coins_bip39::{English, Mnemonic};
+    use serde::Deserialize;
+    use std::str::FromStr;
+    use zksync_basic_types::{H160, H256, U256};
+    use zksync_types::K256PrivateKey;
+
+    use crate::constants::{LEGACY_RICH_WALLETS, RICH_WALLETS};
+
+    /// The default number of accounts derived from the mnemonic.
+    pub const DEFAULT_ACCOUNTS: usize = 10;
+    /// The default balance of the dev accounts, in ETH.
+    pub const DEFAULT_BALANCE: u64 = 1_000_000_000_000;
+    /// The default derivation path, the index of each account is appended to it.
+    pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/60'/0'/0/";
+
+    /// Dev accounts configuration.
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(default)]
+    pub struct AccountsConfig {
+        /// If set, the dev accounts are derived from this BIP-39 mnemonic, instead of being the
+        /// well-known [RICH_WALLETS] and [LEGACY_RICH_WALLETS].
+        pub mnemonic: Option<String>,
+        /// The number of accounts derived from the mnemonic.
+        pub accounts: usize,
+        /// The balance of each dev account, in ETH.
+        pub balance: u64,
+        /// The BIP-32 derivation path of the accounts, the index of each account is appended to it.
+        pub derivation_path: String,
+    }
+
+    impl Default for AccountsConfig {
+        fn default() -> Self {
+            Self {
+                mnemonic: None,
+                accounts: DEFAULT_ACCOUNTS,
+                balance: DEFAULT_BALANCE,
+                derivation_path: DEFAULT_DERIVATION_PATH.to_string(),
+            }
+        }
+    }
+
+    /// An account funded by the node, whose private key it knows.
+    #[derive(Debug, Clone)]
+    pub struct DevAccount {
+        pub address: H160,
+        pub private_key: H256,
+        /// The mnemonic the account was generated from, if it's not derived from the configured one.
+        pub mnemonic: Option<String>,
+    }
+
+    impl DevAccount {
+        fn from_private_key(private_key: H256, mnemonic: Option<String>) -> eyre::Result<Self> {
+            let address = K256PrivateKey::from_bytes(private_key)
+                .map_err(|err| eyre::eyre!("invalid private key: {}", err))?
+                .address();
+            Ok(Self {
+                address,
+                private_key,
+                mnemonic,
+            })
+        }
+    }
+
+    impl AccountsConfig {
+        /// The balance of each dev account, in wei.
+        pub fn balance_wei(&self) -> U256 {
+            U256::from(self.balance) * U256::exp10(18)
+        }
+
+        /// Returns the dev accounts, derived from the mnemonic if one is set, or the well-known rich
+        /// wallets otherwise.
+        pub fn dev_accounts(&self) -> eyre::Result<Vec<DevAccount>> {
+            let Some(phrase) = &self.mnemonic else {
+                return RICH_WALLETS
+                    .iter()
+                    .map(|(_, private_key, mnemonic)| {
+                        DevAccount::from_private_key(
+                            H256::from_str(private_key)?,
+                            Some(mnemonic.to_string()),
+                        )
+                    })
+                    .chain(LEGACY_RICH_WALLETS.iter().map(|(_, private_key)| {
+                        DevAccount::from_private_key(H256::from_str(private_key)?, None)
+                    }))
+                    .collect();
+            };
+
+            let mnemonic = Mnemonic::<English>::new_from_phrase(phrase)?;
+            (0..self.accounts)
+                .map(|index| {
+                    let path = format!("{}{}", self.derivation_path, index);
+                    let key = mnemonic.derive_key(path.as_str(), None)?;
+                    let key: &coins_bip32::prelude::SigningKey = key.as_ref();
+                    DevAccount::from_private_key(H256::from_slice(&key.to_bytes()), None)
+                })
+                .collect()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_dev_accounts_default_to_the_rich_wallets() {
+            let accounts = AccountsConfig::default()
+                .dev_accounts()
+                .expect("failed getting dev accounts");
+
+            assert_eq!(
+                RICH_WALLETS.len() + LEGACY_RICH_WALLETS.len(),
+                accounts.len()
+            );
+            assert_eq!(
+                H160::from_str(RICH_WALLETS[0].0).unwrap(),
+                accounts[0].address
+            );
+        }
+
+        #[test]
+        fn test_dev_accounts_are_derived_from_the_mnemonic() {
+            let config = AccountsConfig {
+                mnemonic: Some(
+                    "test test test test test test test test test test test junk".to_string(),
+                ),
+                accounts: 2,
+                ..Default::default()
+            };
+
+            let accounts = config.dev_accounts().expect("failed getting dev accounts");
+
+            assert_eq!(
+                vec![
+                    H160::from_str("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266").unwrap(),
+                    H160::from_str("0x70997970C51812dc3A010C7d01b50e0d17dc79C8").unwrap(),
+                ],
+                accounts
+                    .iter()
+                    .map(|account| account.address)
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(
+                H256::from_str(
+                    "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+                )
+                .unwrap(),
+                accounts[0].private_key
+            );
+        }
+
+        #[test]
+        fn test_dev_accounts_reject_invalid_mnemonic() {
+            let config = AccountsConfig {
+                mnemonic: Some("not a mnemonic".to_string()),
+                ..Default::default()
+            };
+
+            assert!(config.dev_accounts().is_err());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_rejects_zero_block_gas_limit() {
+        let mut config = TestNodeConfig::default();
+        assert!(config.validate().is_ok());
+
+        config.node.block_gas_limit = 0;
+
+        assert_eq!(
+            "block gas limit must be greater than 0",
+            config.validate().unwrap_err().to_string()
+        );
+    }
+    #[test]
+    fn test_validate_rejects_zero_seal_criteria_limits() {
+        let mut config = TestNodeConfig::default();
+        config.node.seal_criteria.max_pubdata = 0;
+
+        assert_eq!(
+            "seal criteria max_pubdata must be greater than 0",
+            config.validate().unwrap_err().to_string()
+        );
+    }
+}
@@ -1,4 +1,3 @@
-use std::collections::HashMap;
 pub mod system_contracts;
 use zksync_types::{
     get_code_key, get_system_context_init_logs, L2ChainId, StorageKey, StorageLog, StorageLogKind,
@@ -8,10 +7,12 @@ pub mod storage_view;
 use zksync_state::ReadStorage;
 
 /// In-memory storage.
+///
+/// Backed by persistent maps, so clones share the unchanged entries and are cheap to take.
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct InMemoryStorage {
-    pub(crate) state: HashMap<StorageKey, StorageValue>,
-    pub(crate) factory_deps: HashMap<H256, Vec<u8>>,
+    pub(crate) state: im::HashMap<StorageKey, StorageValue>,
+    pub(crate) factory_deps: im::HashMap<H256, Vec<u8>>,
 }
 
 impl InMemoryStorage {
@@ -222,6 +222,15 @@ impl EthFilters {
         })
     }
 
+    /// Notify available filters that a pending transaction was dropped, so it is no longer reported
+    pub fn notify_dropped_transaction(&mut self, hash: H256) {
+        self.filters.iter_mut().for_each(|(_, filter)| {
+            if let FilterType::PendingTransaction(f) = filter {
+                f.updates.retain(|update| update != &hash)
+            }
+        })
+    }
+
     /// Notify available filters of a new transaction log
     pub fn notify_new_log(&mut self, log: &Log, latest_block_number: U64) {
         self.filters.iter_mut().for_each(|(_, filter)| {
@@ -232,6 +241,16 @@ impl EthFilters {
             }
         })
     }
+
+    /// Notify available filters that a transaction log was removed by a chain rollback.
+    /// The `latest_block_number` should be the one the log was added with, so the same filters match it.
+    pub fn notify_removed_log(&mut self, log: &Log, latest_block_number: U64) {
+        let log = Log {
+            removed: Some(true),
+            ..log.clone()
+        };
+        self.notify_new_log(&log, latest_block_number)
+    }
 }
 
 #[cfg(test)]
@@ -405,6 +424,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_notify_removed_log_appends_matching_updates_marked_as_removed() {
+        let mut filters = EthFilters::default();
+        let match_address = H160::repeat_byte(0x1);
+        let id = filters
+            .add_log_filter(
+                BlockNumber::Earliest,
+                BlockNumber::Latest,
+                vec![match_address],
+                Default::default(),
+            )
+            .expect("failed adding filter");
+
+        let log = LogBuilder::new()
+            .set_address(match_address)
+            .set_block(U64::from(1))
+            .build();
+        filters.notify_removed_log(&log, U64::from(1));
+
+        match filters.filters.get(&id).unwrap() {
+            FilterType::Log(f) => {
+                assert_eq!(1, f.updates.len());
+                assert_eq!(Some(true), f.updates[0].removed);
+                assert_eq!(log.block_number, f.updates[0].block_number);
+            }
+            _ => panic!("invalid filter"),
+        }
+    }
+
     #[test]
     fn test_notify_new_pending_transaction_appends_updates() {
         let mut filters = EthFilters::default();
@@ -422,6 +470,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_notify_dropped_transaction_removes_pending_updates() {
+        let mut filters = EthFilters::default();
+        let id = filters
+            .add_pending_transaction_filter()
+            .expect("failed adding filter");
+        filters.notify_new_pending_transaction(H256::repeat_byte(0x1));
+        filters.notify_new_pending_transaction(H256::repeat_byte(0x2));
+
+        filters.notify_dropped_transaction(H256::repeat_byte(0x1));
+
+        match filters.filters.get(&id).unwrap() {
+            FilterType::PendingTransaction(f) => {
+                assert_eq!(vec![H256::repeat_byte(0x2)], f.updates);
+            }
+            _ => panic!("invalid filter"),
+        }
+    }
+
     #[test]
     fn test_get_new_changes_block_returns_updates_and_clears_them() {
         let mut filters = EthFilters::default();
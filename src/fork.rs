@@ -25,7 +25,7 @@ use zksync_types::{
     fee_model::FeeParams,
     l2::L2Tx,
     url::SensitiveUrl,
-    ProtocolVersionId, StorageKey,
+    ProtocolVersionId, StorageKey, StorageValue,
 };
 
 use zksync_state::ReadStorage;
@@ -107,9 +107,12 @@ pub struct ForkStorageInner<S> {
     // Underlying local storage
     pub raw_storage: InMemoryStorage,
     // Cache of data that was read from remote location.
-    pub value_read_cache: HashMap<StorageKey, H256>,
+    pub value_read_cache: im::HashMap<StorageKey, H256>,
     // Cache of factory deps that were read from remote location.
-    pub factory_dep_cache: HashMap<H256, Option<Vec<u8>>>,
+    pub factory_dep_cache: im::HashMap<H256, Option<Vec<u8>>>,
+    // Previous values of the keys written since the state was last archived, used to read the
+    // archived states without keeping a full copy of the storage for every block.
+    pub journal: HashMap<StorageKey, StorageValue>,
     // If set - it hold the necessary information on where to fetch the data.
     // If not set - it will simply read from underlying storage.
     pub fork: Option<ForkDetails>,
@@ -117,6 +120,40 @@ pub struct ForkStorageInner<S> {
     pub dummy: PhantomData<S>,
 }
 
+impl<S> ForkStorageInner<S> {
+    /// Returns the `local_storage` value of `key`, or the value from the fork if it was not written locally.
+    fn read_value_or_fork(
+        &mut self,
+        local_storage: StorageValue,
+        key: &StorageKey,
+    ) -> eyre::Result<StorageValue> {
+        if let Some(fork) = &self.fork {
+            if !H256::is_zero(&local_storage) {
+                return Ok(local_storage);
+            }
+
+            if let Some(value) = self.value_read_cache.get(key) {
+                return Ok(*value);
+            }
+            let l2_miniblock = fork.l2_miniblock;
+            let key_ = *key;
+
+            let result = fork.fork_source.get_storage_at(
+                *key_.account().address(),
+                h256_to_u256(*key_.key()),
+                Some(BlockIdVariant::BlockNumber(BlockNumber::Number(U64::from(
+                    l2_miniblock,
+                )))),
+            )?;
+
+            self.value_read_cache.insert(*key, result);
+            Ok(result)
+        } else {
+            Ok(local_storage)
+        }
+    }
+}
+
 impl<S: ForkSource> ForkStorage<S> {
     pub fn new(
         fork: Option<ForkDetails>,
@@ -138,6 +175,7 @@ impl<S: ForkSource> ForkStorage<S> {
                 value_read_cache: Default::default(),
                 fork,
                 factory_dep_cache: Default::default(),
+                journal: Default::default(),
                 dummy: Default::default(),
             })),
             chain_id,
@@ -178,31 +216,30 @@ impl<S: ForkSource> ForkStorage<S> {
     ) -> eyre::Result<zksync_types::StorageValue> {
         let mut mutator = self.inner.write().unwrap();
         let local_storage = mutator.raw_storage.read_value(key);
+        mutator.read_value_or_fork(local_storage, key)
+    }
 
-        if let Some(fork) = &mutator.fork {
-            if !H256::is_zero(&local_storage) {
-                return Ok(local_storage);
-            }
-
-            if let Some(value) = mutator.value_read_cache.get(key) {
-                return Ok(*value);
-            }
-            let l2_miniblock = fork.l2_miniblock;
-            let key_ = *key;
-
-            let result = fork.fork_source.get_storage_at(
-                *key_.account().address(),
-                h256_to_u256(*key_.key()),
-                Some(BlockIdVariant::BlockNumber(BlockNumber::Number(U64::from(
-                    l2_miniblock,
-                )))),
-            )?;
-
-            mutator.value_read_cache.insert(*key, result);
-            Ok(result)
-        } else {
-            Ok(local_storage)
-        }
+    /// Reads the value at `key` as of a previous local block, given the archived `diffs` of that
+    /// block and all the blocks after it, falling back to the fork for the values that were not
+    /// written locally.
+    ///
+    /// Each diff holds the previous values of the keys written after its block, so the first diff
+    /// that contains `key` has its value as of the requested block. Keys that are in none of them
+    /// were not written since and are read from the latest state.
+    pub fn read_archived_value_internal(
+        &self,
+        diffs: &[&im::HashMap<StorageKey, StorageValue>],
+        key: &StorageKey,
+    ) -> eyre::Result<StorageValue> {
+        let mut mutator = self.inner.write().unwrap();
+        let local_storage = diffs
+            .iter()
+            .find_map(|diff| diff.get(key))
+            .or_else(|| mutator.journal.get(key))
+            .or_else(|| mutator.raw_storage.state.get(key))
+            .copied()
+            .unwrap_or_default();
+        mutator.read_value_or_fork(local_storage, key)
     }
 
     pub fn load_factory_dep_internal(&self, hash: H256) -> eyre::Result<Option<Vec<u8>>> {
@@ -224,6 +261,31 @@ impl<S: ForkSource> ForkStorage<S> {
         }
     }
 
+    /// Reads the value at `key` as of a block before the fork, from the fork source.
+    pub fn read_fork_value_at(
+        &self,
+        key: &StorageKey,
+        block_number: u64,
+    ) -> eyre::Result<StorageValue> {
+        let reader = self
+            .inner
+            .read()
+            .map_err(|err| eyre!("failed acquiring read lock on fork storage: {:?}", err))?;
+        let fork = reader.fork.as_ref().ok_or_else(|| {
+            eyre!(
+                "block #{} is not available, node is not forked",
+                block_number
+            )
+        })?;
+        fork.fork_source.get_storage_at(
+            *key.account().address(),
+            h256_to_u256(*key.key()),
+            Some(BlockIdVariant::BlockNumber(BlockNumber::Number(U64::from(
+                block_number,
+            )))),
+        )
+    }
+
     /// Check if this is the first time when we're ever writing to this key.
     /// This has impact on amount of pubdata that we have to spend for the write.
     pub fn is_write_initial_internal(&self, key: &StorageKey) -> eyre::Result<bool> {
@@ -291,11 +353,114 @@ impl<S: std::fmt::Debug + ForkSource> ReadStorage for &ForkStorage<S> {
     }
 }
 
+/// The state of a previous block, as read by a [HistoricalStorage].
+#[derive(Debug)]
+pub enum HistoricalState<'a> {
+    /// The state of a local block, given the archived diffs of that block and all the blocks after it.
+    Archived(Vec<&'a im::HashMap<StorageKey, StorageValue>>),
+    /// The state of a block before the fork, which is read from the fork source.
+    Fork(u64),
+}
+
+/// A read-only view of a [ForkStorage] as of a previous block. Without a [HistoricalState], the latest
+/// values are read.
+#[derive(Debug)]
+pub struct HistoricalStorage<'a, S> {
+    fork_storage: &'a ForkStorage<S>,
+    state: Option<HistoricalState<'a>>,
+}
+
+impl<'a, S: ForkSource> HistoricalStorage<'a, S> {
+    pub fn new(fork_storage: &'a ForkStorage<S>, state: Option<HistoricalState<'a>>) -> Self {
+        Self {
+            fork_storage,
+            state,
+        }
+    }
+
+    pub fn read_value_internal(&self, key: &StorageKey) -> eyre::Result<StorageValue> {
+        match &self.state {
+            Some(HistoricalState::Archived(diffs)) => {
+                self.fork_storage.read_archived_value_internal(diffs, key)
+            }
+            Some(HistoricalState::Fork(block_number)) => {
+                self.fork_storage.read_fork_value_at(key, *block_number)
+            }
+            None => self.fork_storage.read_value_internal(key),
+        }
+    }
+
+    pub fn load_factory_dep_internal(&self, hash: H256) -> eyre::Result<Option<Vec<u8>>> {
+        // factory deps are never removed, so the latest ones are a superset of the archived ones
+        self.fork_storage.load_factory_dep_internal(hash)
+    }
+
+    fn is_write_initial_internal(&self, key: &StorageKey) -> eyre::Result<bool> {
+        let diffs = match &self.state {
+            Some(HistoricalState::Archived(diffs)) => diffs,
+            // no key was written locally before the fork, so only the value tells if it was written
+            Some(HistoricalState::Fork(_)) => return Ok(self.read_value_internal(key)?.is_zero()),
+            None => return self.fork_storage.is_write_initial_internal(key),
+        };
+        if !self.read_value_internal(key)?.is_zero() {
+            return Ok(false);
+        }
+
+        // the diffs don't tell apart keys that were zero from keys that were not written at all,
+        // so a key that was written since the requested block is considered an initial write
+        let written_since = diffs.iter().any(|diff| diff.contains_key(key))
+            || self
+                .fork_storage
+                .inner
+                .read()
+                .map_err(|err| eyre!("failed acquiring read lock on fork storage: {:?}", err))?
+                .journal
+                .contains_key(key);
+        if written_since {
+            Ok(true)
+        } else {
+            self.fork_storage.is_write_initial_internal(key)
+        }
+    }
+}
+
+impl<'a, S: std::fmt::Debug + ForkSource> ReadStorage for HistoricalStorage<'a, S> {
+    fn read_value(&mut self, key: &StorageKey) -> zksync_types::StorageValue {
+        self.read_value_internal(key).unwrap()
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        self.is_write_initial_internal(key).unwrap()
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        self.load_factory_dep_internal(hash).unwrap()
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        self.fork_storage.get_enumeration_index_internal(key)
+    }
+}
+
 impl<S> ForkStorage<S> {
     pub fn set_value(&mut self, key: StorageKey, value: zksync_types::StorageValue) {
         let mut mutator = self.inner.write().unwrap();
+        let previous_value = mutator
+            .raw_storage
+            .state
+            .get(&key)
+            .copied()
+            .unwrap_or_default();
+        mutator.journal.entry(key).or_insert(previous_value);
         mutator.raw_storage.set_value(key, value)
     }
+
+    /// Returns the previous values of the keys written since the last call, and starts a new journal.
+    pub fn take_journal(&mut self) -> HashMap<StorageKey, StorageValue> {
+        let mut mutator = self.inner.write().unwrap();
+        std::mem::take(&mut mutator.journal)
+    }
+
     pub fn store_factory_dep(&mut self, hash: H256, bytecode: Vec<u8>) {
         let mut mutator = self.inner.write().unwrap();
         mutator.raw_storage.store_factory_dep(hash, bytecode)
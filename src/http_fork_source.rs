@@ -7,6 +7,7 @@ use crate::{
     cache::Cache,
     config::cache::CacheConfig,
     fork::{block_on, ForkSource},
+    metrics,
 };
 use eyre::Context;
 use zksync_basic_types::{H256, U256};
@@ -45,6 +46,12 @@ impl HttpForkSource {
             .unwrap_or_else(|_| panic!("Unable to create a client for fork: {}", self.fork_url))
             .build()
     }
+
+    /// Creates a client for a request to the fork source, recording it in the metrics.
+    fn request_client(&self, method: &'static str) -> Client<L2> {
+        metrics::FORK_REQUESTS.with_label_values(&[method]).inc();
+        self.create_client()
+    }
 }
 
 impl ForkSource for HttpForkSource {
@@ -58,7 +65,7 @@ impl ForkSource for HttpForkSource {
         idx: zksync_basic_types::U256,
         block: Option<zksync_types::api::BlockIdVariant>,
     ) -> eyre::Result<zksync_basic_types::H256> {
-        let client = self.create_client();
+        let client = self.request_client("get_storage_at");
         block_on(async move { client.get_storage_at(address, idx, block).await })
             .wrap_err("fork http client failed")
     }
@@ -67,7 +74,7 @@ impl ForkSource for HttpForkSource {
         &self,
         hash: zksync_basic_types::H256,
     ) -> eyre::Result<Option<Vec<u8>>> {
-        let client = self.create_client();
+        let client = self.request_client("get_bytecode_by_hash");
         block_on(async move { client.get_bytecode_by_hash(hash).await })
             .wrap_err("fork http client failed")
     }
@@ -85,7 +92,7 @@ impl ForkSource for HttpForkSource {
             return Ok(Some(transaction));
         }
 
-        let client = self.create_client();
+        let client = self.request_client("get_transaction_by_hash");
         block_on(async move { client.get_transaction_by_hash(hash).await })
             .map(|maybe_transaction| {
                 if let Some(transaction) = &maybe_transaction {
@@ -108,7 +115,7 @@ impl ForkSource for HttpForkSource {
         &self,
         hash: H256,
     ) -> eyre::Result<Option<zksync_types::api::TransactionDetails>> {
-        let client = self.create_client();
+        let client = self.request_client("get_transaction_details");
         // n.b- We don't cache these responses as they will change through the lifecycle of the transaction
         // and caching could be error-prone. in theory we could cache responses once the txn status
         // is `final` or `failed` but currently this does not warrant the additional complexity.
@@ -130,7 +137,7 @@ impl ForkSource for HttpForkSource {
             return Ok(transaction);
         }
 
-        let client = self.create_client();
+        let client = self.request_client("get_raw_block_transactions");
         block_on(async move { client.get_raw_block_transactions(block_number).await })
             .wrap_err("fork http client failed")
             .map(|transactions| {
@@ -165,7 +172,7 @@ impl ForkSource for HttpForkSource {
             return Ok(Some(block));
         }
 
-        let client = self.create_client();
+        let client = self.request_client("get_block_by_hash");
         block_on(async move { client.get_block_by_hash(hash, full_transactions).await })
             .map(|block| {
                 if let Some(block) = &block {
@@ -205,7 +212,7 @@ impl ForkSource for HttpForkSource {
             return Ok(Some(block));
         }
 
-        let client = self.create_client();
+        let client = self.request_client("get_block_by_number");
         block_on(async move {
             client
                 .get_block_by_number(block_number, full_transactions)
@@ -232,7 +239,7 @@ impl ForkSource for HttpForkSource {
 
     /// Returns the  transaction count for a given block hash.
     fn get_block_transaction_count_by_hash(&self, block_hash: H256) -> eyre::Result<Option<U256>> {
-        let client = self.create_client();
+        let client = self.request_client("get_block_transaction_count_by_hash");
         block_on(async move { client.get_block_transaction_count_by_hash(block_hash).await })
             .wrap_err("fork http client failed")
     }
@@ -242,7 +249,7 @@ impl ForkSource for HttpForkSource {
         &self,
         block_number: zksync_types::api::BlockNumber,
     ) -> eyre::Result<Option<U256>> {
-        let client = self.create_client();
+        let client = self.request_client("get_block_transaction_count_by_number");
         block_on(async move {
             client
                 .get_block_transaction_count_by_number(block_number)
@@ -257,7 +264,7 @@ impl ForkSource for HttpForkSource {
         block_hash: H256,
         index: Index,
     ) -> eyre::Result<Option<Transaction>> {
-        let client = self.create_client();
+        let client = self.request_client("get_transaction_by_block_hash_and_index");
         block_on(async move {
             client
                 .get_transaction_by_block_hash_and_index(block_hash, index)
@@ -272,7 +279,7 @@ impl ForkSource for HttpForkSource {
         block_number: zksync_types::api::BlockNumber,
         index: Index,
     ) -> eyre::Result<Option<Transaction>> {
-        let client = self.create_client();
+        let client = self.request_client("get_transaction_by_block_number_and_index");
         block_on(async move {
             client
                 .get_transaction_by_block_number_and_index(block_number, index)
@@ -286,7 +293,7 @@ impl ForkSource for HttpForkSource {
         &self,
         miniblock: zksync_basic_types::L2BlockNumber,
     ) -> eyre::Result<Option<zksync_types::api::BlockDetails>> {
-        let client = self.create_client();
+        let client = self.request_client("get_block_details");
         block_on(async move { client.get_block_details(miniblock).await }).wrap_err(format!(
             "Failed to get block details for {} l2 block in fork http client",
             miniblock
@@ -295,7 +302,7 @@ impl ForkSource for HttpForkSource {
 
     /// Returns fee parameters for the give source.
     fn get_fee_params(&self) -> eyre::Result<zksync_types::fee_model::FeeParams> {
-        let client = self.create_client();
+        let client = self.request_client("get_fee_params");
         block_on(async move { client.get_fee_params().await }).wrap_err("fork http client failed")
     }
 
@@ -311,7 +318,7 @@ impl ForkSource for HttpForkSource {
             return Ok(bridge_addresses);
         };
 
-        let client = self.create_client();
+        let client = self.request_client("get_bridge_contracts");
         block_on(async move { client.get_bridge_contracts().await })
             .map(|bridge_addresses| {
                 self.cache
@@ -340,7 +347,7 @@ impl ForkSource for HttpForkSource {
             return Ok(confirmed_tokens);
         };
 
-        let client = self.create_client();
+        let client = self.request_client("get_confirmed_tokens");
         block_on(async move { client.get_confirmed_tokens(from, limit).await })
             .map(|confirmed_tokens| {
                 self.cache
@@ -29,8 +29,7 @@
 //!
 //! - No communication between Layer 1 and Layer 2.
 //! - Many APIs are not yet implemented.
-//! - No support for accessing historical data.
-//! - Only one transaction allowed per Layer 1 batch.
+//! - Historical state is limited to the last 128 blocks, unless running in archive mode.
 //!
 //! ## Usage
 //!
@@ -50,10 +49,12 @@ pub mod filters;
 pub mod fork;
 pub mod formatter;
 pub mod http_fork_source;
+pub mod metrics;
 pub mod namespaces;
 pub mod node;
 pub mod observability;
 pub mod resolver;
+pub mod subscriptions;
 pub mod system_contracts;
 pub mod utils;
 
@@ -3,13 +3,13 @@ use futures::Future;
 use futures::{future::Either, FutureExt};
 use itertools::Itertools;
 use jsonrpc_core::{
-    middleware, Call, FutureResponse, Metadata, Middleware, Params, Request, Response,
+    Call, FutureOutput, FutureResponse, Metadata, MethodCall, Middleware, Output, Params, Request,
+    Response,
 };
+use std::time::Instant;
 use tracing_subscriber::filter::LevelFilter;
 
-#[derive(Clone, Debug, Default)]
-pub struct Meta();
-impl Metadata for Meta {}
+use crate::metrics;
 
 pub struct LoggingMiddleware {
     log_level_filter: LevelFilter,
@@ -19,75 +19,108 @@ impl LoggingMiddleware {
     pub fn new(log_level_filter: LevelFilter) -> Self {
         Self { log_level_filter }
     }
+
+    /// Logs the called method, with its params truncated unless tracing.
+    fn log_method_call(&self, method_call: &MethodCall) {
+        match self.log_level_filter {
+            LevelFilter::TRACE => {
+                let full_params = match &method_call.params {
+                    Params::Array(values) => {
+                        if values.is_empty() {
+                            String::default()
+                        } else {
+                            format!("with [{}]", values.iter().join(", "))
+                        }
+                    }
+                    _ => String::default(),
+                };
+
+                tracing::trace!("{} was called {}", method_call.method.cyan(), full_params);
+            }
+            _ => {
+                // Generate truncated params for requests with massive payloads
+                let truncated_params = match &method_call.params {
+                    Params::Array(values) => {
+                        if values.is_empty() {
+                            String::default()
+                        } else {
+                            format!(
+                                "with [{}]",
+                                values
+                                    .iter()
+                                    .map(|s| {
+                                        let s_str = s.to_string();
+                                        if s_str.len() > 70 {
+                                            format!("{:.67}...", s_str)
+                                        } else {
+                                            s_str
+                                        }
+                                    })
+                                    .collect::<Vec<String>>()
+                                    .join(", ")
+                            )
+                        }
+                    }
+                    _ => String::default(),
+                };
+
+                tracing::debug!(
+                    "{} was called {}",
+                    method_call.method.cyan(),
+                    truncated_params
+                );
+            }
+        }
+    }
 }
 
 /// Logging Middleware for all in-bound requests
 /// Logs out incoming requests and their parameters
 /// Useful for debugging applications that are pointed at this service
-impl Middleware<Meta> for LoggingMiddleware {
+impl<M: Metadata> Middleware<M> for LoggingMiddleware {
     type Future = FutureResponse;
-    type CallFuture = middleware::NoopCallFuture;
+    type CallFuture = FutureOutput;
 
-    fn on_request<F, X>(&self, request: Request, meta: Meta, next: F) -> Either<Self::Future, X>
+    fn on_request<F, X>(&self, request: Request, meta: M, next: F) -> Either<Self::Future, X>
     where
-        F: FnOnce(Request, Meta) -> X + Send,
+        F: FnOnce(Request, M) -> X + Send,
         X: Future<Output = Option<Response>> + Send + 'static,
     {
-        if let Request::Single(Call::MethodCall(method_call)) = &request {
-            match self.log_level_filter {
-                LevelFilter::TRACE => {
-                    let full_params = match &method_call.params {
-                        Params::Array(values) => {
-                            if values.is_empty() {
-                                String::default()
-                            } else {
-                                format!("with [{}]", values.iter().join(", "))
-                            }
-                        }
-                        _ => String::default(),
-                    };
-
-                    tracing::trace!("{} was called {}", method_call.method.cyan(), full_params);
-                }
-                _ => {
-                    // Generate truncated params for requests with massive payloads
-                    let truncated_params = match &method_call.params {
-                        Params::Array(values) => {
-                            if values.is_empty() {
-                                String::default()
-                            } else {
-                                format!(
-                                    "with [{}]",
-                                    values
-                                        .iter()
-                                        .map(|s| {
-                                            let s_str = s.to_string();
-                                            if s_str.len() > 70 {
-                                                format!("{:.67}...", s_str)
-                                            } else {
-                                                s_str
-                                            }
-                                        })
-                                        .collect::<Vec<String>>()
-                                        .join(", ")
-                                )
-                            }
-                        }
-                        _ => String::default(),
-                    };
-
-                    tracing::debug!(
-                        "{} was called {}",
-                        method_call.method.cyan(),
-                        truncated_params
-                    );
+        match &request {
+            Request::Single(Call::MethodCall(method_call)) => self.log_method_call(method_call),
+            Request::Batch(calls) => {
+                tracing::debug!("batch of {} calls", calls.len());
+                for call in calls {
+                    if let Call::MethodCall(method_call) = call {
+                        self.log_method_call(method_call);
+                    }
                 }
             }
-        };
+            _ => {}
+        }
 
         Either::Left(Box::pin(next(request, meta).map(move |res| {
             tracing::trace!("API response => {:?}", res);
             res
         })))
     }
+
+    /// Measures each call, including the ones in a batch request.
+    fn on_call<F, X>(&self, call: Call, meta: M, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: FnOnce(Call, M) -> X + Send,
+        X: Future<Output = Option<Output>> + Send + 'static,
+    {
+        let method = match &call {
+            Call::MethodCall(method_call) => method_call.method.clone(),
+            Call::Notification(notification) => notification.method.clone(),
+            Call::Invalid { .. } => return Either::Right(next(call, meta)),
+        };
+
+        let started_at = Instant::now();
+        Either::Left(Box::pin(next(call, meta).map(move |output| {
+            metrics::observe_rpc_call(&method, started_at.elapsed(), output.as_ref());
+            output
+        })))
+    }
 }
@@ -3,7 +3,7 @@ use anyhow::anyhow;
 use clap::Parser;
 use colored::Colorize;
 use config::cli::{Cli, Command};
-use config::TestNodeConfig;
+use config::{server::ServerConfig, TestNodeConfig};
 use fork::{ForkDetails, ForkSource};
 use http_fork_source::HttpForkSource;
 use logging_middleware::LoggingMiddleware;
@@ -20,21 +20,24 @@ mod fork;
 mod formatter;
 mod http_fork_source;
 mod logging_middleware;
+mod metrics;
 mod namespaces;
 mod node;
 mod observability;
 mod resolver;
+mod subscriptions;
 mod system_contracts;
 mod testing;
 mod utils;
 
-use node::InMemoryNode;
+use node::{BlockProducer, InMemoryNode};
 
 use std::fs::File;
 use std::{
     env,
     net::{IpAddr, Ipv4Addr, SocketAddr},
-    str::FromStr,
+    path::PathBuf,
+    sync::Arc,
 };
 
 use futures::{
@@ -43,40 +46,57 @@ use futures::{
     FutureExt,
 };
 use jsonrpc_core::MetaIoHandler;
-use zksync_basic_types::H160;
+use jsonrpc_http_server::{
+    hyper, AccessControlAllowOrigin, CloseHandle, DomainsValidation, Response,
+};
+use jsonrpc_pubsub::Session;
 
-use crate::constants::{LEGACY_RICH_WALLETS, RICH_WALLETS};
 use crate::namespaces::{
     AnvilNamespaceT, ConfigurationApiNamespaceT, DebugNamespaceT, EthNamespaceT,
-    EthTestNodeNamespaceT, EvmNamespaceT, HardhatNamespaceT, NetNamespaceT, Web3NamespaceT,
-    ZksNamespaceT,
+    EthPubSubNamespaceT, EthTestNodeNamespaceT, EvmNamespaceT, HardhatNamespaceT, NetNamespaceT,
+    TxpoolNamespaceT, Web3NamespaceT, ZksNamespaceT,
 };
 
+/// Session metadata of a request, only present for the WebSocket transport, where subscriptions are supported.
+type Meta = Option<Arc<Session>>;
+
+fn build_io_handler<
+    S: std::marker::Sync + std::marker::Send + 'static + ForkSource + std::fmt::Debug + Clone,
+>(
+    log_level_filter: LevelFilter,
+    node: InMemoryNode<S>,
+) -> MetaIoHandler<Meta, LoggingMiddleware> {
+    let mut io = MetaIoHandler::with_middleware(LoggingMiddleware::new(log_level_filter));
+
+    io.extend_with(NetNamespaceT::to_delegate(node.clone()));
+    io.extend_with(Web3NamespaceT::to_delegate(node.clone()));
+    io.extend_with(ConfigurationApiNamespaceT::to_delegate(node.clone()));
+    io.extend_with(DebugNamespaceT::to_delegate(node.clone()));
+    io.extend_with(EthNamespaceT::to_delegate(node.clone()));
+    io.extend_with(EthPubSubNamespaceT::to_delegate(node.clone()));
+    io.extend_with(EthTestNodeNamespaceT::to_delegate(node.clone()));
+    io.extend_with(AnvilNamespaceT::to_delegate(node.clone()));
+    io.extend_with(EvmNamespaceT::to_delegate(node.clone()));
+    io.extend_with(HardhatNamespaceT::to_delegate(node.clone()));
+    io.extend_with(TxpoolNamespaceT::to_delegate(node.clone()));
+    io.extend_with(ZksNamespaceT::to_delegate(node));
+    io
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn build_json_http<
     S: std::marker::Sync + std::marker::Send + 'static + ForkSource + std::fmt::Debug + Clone,
 >(
     addr: SocketAddr,
+    server_config: ServerConfig,
     log_level_filter: LevelFilter,
     node: InMemoryNode<S>,
-) -> tokio::task::JoinHandle<()> {
+) -> (tokio::task::JoinHandle<()>, CloseHandle) {
     let (sender, recv) = oneshot::channel::<()>();
+    let (close_handle_sender, close_handle_recv) = std::sync::mpsc::channel();
 
-    let io_handler = {
-        let mut io = MetaIoHandler::with_middleware(LoggingMiddleware::new(log_level_filter));
-
-        io.extend_with(NetNamespaceT::to_delegate(node.clone()));
-        io.extend_with(Web3NamespaceT::to_delegate(node.clone()));
-        io.extend_with(ConfigurationApiNamespaceT::to_delegate(node.clone()));
-        io.extend_with(DebugNamespaceT::to_delegate(node.clone()));
-        io.extend_with(EthNamespaceT::to_delegate(node.clone()));
-        io.extend_with(EthTestNodeNamespaceT::to_delegate(node.clone()));
-        io.extend_with(AnvilNamespaceT::to_delegate(node.clone()));
-        io.extend_with(EvmNamespaceT::to_delegate(node.clone()));
-        io.extend_with(HardhatNamespaceT::to_delegate(node.clone()));
-        io.extend_with(ZksNamespaceT::to_delegate(node));
-        io
-    };
+    let metrics_node = server_config.metrics.then(|| node.clone());
+    let io_handler = build_io_handler(log_level_filter, node);
 
     std::thread::spawn(move || {
         let runtime = tokio::runtime::Builder::new_multi_thread()
@@ -88,14 +108,209 @@ async fn build_json_http<
         let server = jsonrpc_http_server::ServerBuilder::new(io_handler)
             .threads(1)
             .event_loop_executor(runtime.handle().clone())
+            .request_middleware(
+                move |request: hyper::Request<hyper::Body>| match &metrics_node {
+                    Some(node)
+                        if request.method() == hyper::Method::GET
+                            && request.uri().path() == "/metrics" =>
+                    {
+                        metrics_response(node).into()
+                    }
+                    _ => request.into(),
+                },
+            )
+            .cors(DomainsValidation::AllowOnly(
+                server_config
+                    .allow_origin
+                    .into_iter()
+                    .map(AccessControlAllowOrigin::from)
+                    .collect(),
+            ))
+            .max_request_body_size(server_config.max_request_size)
             .start_http(&addr)
             .unwrap();
 
+        let _ = close_handle_sender.send(server.close_handle());
+        server.wait();
+        let _ = sender;
+    });
+
+    let close_handle = close_handle_recv
+        .recv()
+        .expect("failed starting the HTTP server");
+    (tokio::spawn(recv.map(drop)), close_handle)
+}
+
+/// Responds to `GET /metrics` with the Prometheus metrics of the node.
+fn metrics_response<
+    S: std::marker::Sync + std::marker::Send + 'static + ForkSource + std::fmt::Debug + Clone,
+>(
+    node: &InMemoryNode<S>,
+) -> Response {
+    match node.snapshot_count() {
+        Ok(count) => metrics::SNAPSHOTS.set(count as i64),
+        Err(err) => tracing::warn!("failed counting snapshots: {:?}", err),
+    }
+
+    match metrics::encode() {
+        Ok(content) => Response {
+            code: hyper::StatusCode::OK,
+            content_type: hyper::header::HeaderValue::from_static("text/plain; version=0.0.4"),
+            content,
+        },
+        Err(err) => Response::internal_error(err),
+    }
+}
+
+/// The origins the WebSocket server accepts connections from, `*` allows any origin.
+fn ws_allowed_origins(
+    allow_origin: &[String],
+) -> jsonrpc_ws_server::DomainsValidation<jsonrpc_ws_server::Origin> {
+    if allow_origin.iter().any(|origin| origin == "*") {
+        jsonrpc_ws_server::DomainsValidation::Disabled
+    } else {
+        jsonrpc_ws_server::DomainsValidation::AllowOnly(
+            allow_origin
+                .iter()
+                .map(|origin| jsonrpc_ws_server::Origin::from(origin.as_str()))
+                .collect(),
+        )
+    }
+}
+
+async fn build_json_ws<
+    S: std::marker::Sync + std::marker::Send + 'static + ForkSource + std::fmt::Debug + Clone,
+>(
+    addr: SocketAddr,
+    server_config: ServerConfig,
+    log_level_filter: LevelFilter,
+    node: InMemoryNode<S>,
+) -> (tokio::task::JoinHandle<()>, jsonrpc_ws_server::CloseHandle) {
+    let (sender, recv) = oneshot::channel::<()>();
+    let (close_handle_sender, close_handle_recv) = std::sync::mpsc::channel();
+
+    let io_handler = build_io_handler(log_level_filter, node);
+
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .worker_threads(1)
+            .build()
+            .unwrap();
+
+        let server = jsonrpc_ws_server::ServerBuilder::with_meta_extractor(
+            io_handler,
+            |context: &jsonrpc_ws_server::RequestContext| {
+                Some(Arc::new(Session::new(context.sender())))
+            },
+        )
+        .event_loop_executor(runtime.handle().clone())
+        .allowed_origins(ws_allowed_origins(&server_config.allow_origin))
+        .max_payload(server_config.max_request_size)
+        .start(&addr)
+        .unwrap();
+
+        let _ = close_handle_sender.send(server.close_handle());
+        let _ = server.wait();
+        let _ = sender;
+    });
+
+    let close_handle = close_handle_recv
+        .recv()
+        .expect("failed starting the WebSocket server");
+    (tokio::spawn(recv.map(drop)), close_handle)
+}
+
+/// Removes the socket left at `path` by a previous run, which would fail the bind. Anything else at `path`,
+/// including a socket another process still listens on, is an error.
+#[cfg(unix)]
+fn remove_stale_ipc_socket(path: &str) -> anyhow::Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(anyhow!("failed reading IPC path {}: {}", path, err)),
+    };
+    if !metadata.file_type().is_socket() {
+        return Err(anyhow!("IPC path {} exists and is not a socket", path));
+    }
+    if std::os::unix::net::UnixStream::connect(path).is_ok() {
+        return Err(anyhow!("IPC path {} is in use by another process", path));
+    }
+    std::fs::remove_file(path)
+        .map_err(|err| anyhow!("failed removing stale IPC socket {}: {}", path, err))
+}
+
+#[cfg(not(unix))]
+fn remove_stale_ipc_socket(_path: &str) -> anyhow::Result<()> {
+    Ok(())
+}
+
+async fn build_json_ipc<
+    S: std::marker::Sync + std::marker::Send + 'static + ForkSource + std::fmt::Debug + Clone,
+>(
+    path: String,
+    log_level_filter: LevelFilter,
+    node: InMemoryNode<S>,
+) -> anyhow::Result<(tokio::task::JoinHandle<()>, jsonrpc_ipc_server::CloseHandle)> {
+    let (sender, recv) = oneshot::channel::<()>();
+    let (close_handle_sender, close_handle_recv) = std::sync::mpsc::channel();
+
+    let io_handler = build_io_handler(log_level_filter, node);
+
+    remove_stale_ipc_socket(&path)?;
+
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .worker_threads(1)
+            .build()
+            .unwrap();
+
+        let server = jsonrpc_ipc_server::ServerBuilder::with_meta_extractor(
+            io_handler,
+            |context: &jsonrpc_ipc_server::RequestContext| {
+                Some(Arc::new(Session::new(context.sender.clone())))
+            },
+        )
+        .event_loop_executor(runtime.handle().clone())
+        .start(&path)
+        .unwrap();
+
+        let _ = close_handle_sender.send(server.close_handle());
         server.wait();
         let _ = sender;
     });
 
-    tokio::spawn(recv.map(drop))
+    let close_handle = close_handle_recv
+        .recv()
+        .expect("failed starting the IPC server");
+    Ok((tokio::spawn(recv.map(drop)), close_handle))
+}
+
+/// Resolves once the process is asked to terminate, either via SIGINT (Ctrl-C) or SIGTERM.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed installing the Ctrl-C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed installing the SIGTERM handler")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
 }
 
 #[tokio::main]
@@ -105,6 +320,7 @@ async fn main() -> anyhow::Result<()> {
     // Try to read the [`TestNodeConfig`] file if supplied as an argument.
     let mut config = TestNodeConfig::try_load(&opt.config).unwrap_or_default();
     config.override_with_opts(&opt);
+    config.validate().map_err(|error| anyhow!(error))?;
 
     let log_level_filter = LevelFilter::from(config.log.level);
     let log_file = File::create(config.log.file_path)?;
@@ -168,48 +384,161 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
-    let node: InMemoryNode<HttpForkSource> =
-        InMemoryNode::new(fork_details, Some(observability), config.node, config.gas);
+    let dev_accounts = match config.accounts.dev_accounts() {
+        Ok(dev_accounts) => dev_accounts,
+        Err(error) => {
+            tracing::error!("cannot derive the dev accounts: {:?}", error);
+            return Err(anyhow!(error));
+        }
+    };
+
+    let node: InMemoryNode<HttpForkSource> = InMemoryNode::new(
+        fork_details,
+        Some(observability.clone()),
+        config.node,
+        config.gas,
+    )
+    .with_snapshot_dir(config.state.snapshot_dir.clone().map(PathBuf::from))
+    .with_dev_accounts(dev_accounts.clone(), config.accounts.balance_wei());
 
     if !transactions_to_replay.is_empty() {
         let _ = node.apply_txs(transactions_to_replay);
     }
 
+    node.fund_dev_accounts();
     tracing::info!("");
     tracing::info!("Rich Accounts");
     tracing::info!("=============");
-    for wallet in LEGACY_RICH_WALLETS.iter() {
-        let address = wallet.0;
-        node.set_rich_account(H160::from_str(address).unwrap());
-    }
-    for (index, wallet) in RICH_WALLETS.iter().enumerate() {
-        let address = wallet.0;
-        let private_key = wallet.1;
-        let mnemonic_phrase = wallet.2;
-        node.set_rich_account(H160::from_str(address).unwrap());
+    for (index, account) in dev_accounts.iter().enumerate() {
         tracing::info!(
-            "Account #{}: {} ({})",
+            "Account #{}: {:#x} ({})",
             index,
-            address,
-            "1_000_000_000_000 ETH".cyan()
+            account.address,
+            format!("{} ETH", config.accounts.balance).cyan()
         );
-        tracing::info!("Private Key: {}", private_key);
-        tracing::info!("Mnemonic: {}", &mnemonic_phrase.truecolor(128, 128, 128));
+        tracing::info!("Private Key: {:#x}", account.private_key);
+        if let Some(mnemonic_phrase) = &account.mnemonic {
+            tracing::info!("Mnemonic: {}", mnemonic_phrase.truecolor(128, 128, 128));
+        }
+        tracing::info!("");
+    }
+    if let Some(mnemonic_phrase) = &config.accounts.mnemonic {
+        tracing::info!("Mnemonic: {}", mnemonic_phrase.truecolor(128, 128, 128));
+        tracing::info!("Derivation path: {}", config.accounts.derivation_path);
         tracing::info!("");
     }
 
-    let threads = build_json_http(
-        SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), config.node.port),
+    if let Some(path) = &config.state.load_path {
+        match std::fs::read(path) {
+            Ok(buf) => {
+                node.load_state(buf.into())?;
+                tracing::info!("Loaded state from {}", path);
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                tracing::warn!("State file {} not found, starting with a fresh state", path);
+            }
+            Err(err) => return Err(anyhow!("failed reading state from {}: {}", path, err)),
+        }
+    }
+
+    if let Some(block_time) = config.node.block_time {
+        tracing::info!("Interval mining enabled, block time: {}ms", block_time);
+    }
+    let block_producer = tokio::spawn(BlockProducer::new(node.clone()).run());
+
+    // the address to reach the node at, when listening on all interfaces
+    let ready_host = if config.server.host.is_unspecified() {
+        IpAddr::V4(Ipv4Addr::LOCALHOST)
+    } else {
+        config.server.host
+    };
+    let (threads, close_handle) = build_json_http(
+        SocketAddr::new(config.server.host, config.server.port),
+        config.server.clone(),
         log_level_filter,
-        node,
+        node.clone(),
     )
     .await;
+    let mut threads = vec![threads];
+    let ws_close_handle = if let Some(ws_port) = config.server.ws_port {
+        let (ws_threads, ws_close_handle) = build_json_ws(
+            SocketAddr::new(config.server.host, ws_port),
+            config.server.clone(),
+            log_level_filter,
+            node.clone(),
+        )
+        .await;
+        threads.push(ws_threads);
+        Some(ws_close_handle)
+    } else {
+        None
+    };
+    let ipc_close_handle = if let Some(ipc_path) = config.server.ipc_path.clone() {
+        let (ipc_threads, ipc_close_handle) =
+            build_json_ipc(ipc_path, log_level_filter, node.clone()).await?;
+        threads.push(ipc_threads);
+        Some(ipc_close_handle)
+    } else {
+        None
+    };
 
     tracing::info!("========================================");
-    tracing::info!("  Node is ready at 127.0.0.1:{}", config.node.port);
+    tracing::info!("  Node is ready at {}:{}", ready_host, config.server.port);
+    if let Some(ws_port) = config.server.ws_port {
+        tracing::info!("  WebSocket is ready at ws://{}:{}", ready_host, ws_port);
+    }
+    if let Some(ipc_path) = &config.server.ipc_path {
+        tracing::info!("  IPC is ready at {}", ipc_path);
+    }
     tracing::info!("========================================");
 
-    future::select_all(vec![threads]).await.0.unwrap();
+    tokio::select! {
+        result = future::select_all(threads) => result.0.unwrap(),
+        _ = shutdown_signal() => {
+            tracing::info!("Shutting down, no longer accepting requests");
+            close_handle.close();
+            if let Some(ws_close_handle) = ws_close_handle {
+                ws_close_handle.close();
+            }
+            if let Some(ipc_close_handle) = ipc_close_handle {
+                ipc_close_handle.close();
+            }
+        }
+    }
+
+    // the socket is not removed when the IPC server is closed, and would be left behind
+    #[cfg(unix)]
+    if let Some(ipc_path) = &config.server.ipc_path {
+        match std::fs::remove_file(ipc_path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                tracing::warn!("failed removing IPC socket {}: {}", ipc_path, err)
+            }
+            _ => {}
+        }
+    }
+
+    // Stop mining new blocks, and wait for the transaction in progress (if any) to be sealed,
+    // as it holds the write lock on the node until it's done. The lock is then kept, so requests
+    // that are still being served can't modify the state after it's dumped.
+    block_producer.abort();
+    let inner = node.get_inner();
+    let inner = inner
+        .write()
+        .map_err(|err| anyhow!("failed acquiring lock: {:?}", err))?;
+
+    if let Some(path) = &config.state.dump_path {
+        match inner
+            .dump_state()
+            .map_err(|err| anyhow!(err))
+            .and_then(|state| serde_json::to_vec(&state).map_err(anyhow::Error::from))
+            .and_then(|state| std::fs::write(path, state).map_err(anyhow::Error::from))
+        {
+            Ok(()) => tracing::info!("Dumped state to {}", path),
+            Err(err) => tracing::error!("failed writing state to {}: {:?}", path, err),
+        }
+    }
+
+    observability.flush()?;
 
     Ok(())
 }
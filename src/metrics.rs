@@ -0,0 +1,120 @@
+//! Prometheus metrics of the node, served at `/metrics` when enabled via `--metrics`.
+use std::time::Duration;
+
+use jsonrpc_core::{ErrorCode, Output};
+use lazy_static::lazy_static;
+use multivm::interface::ExecutionResult;
+use prometheus::{
+    register_histogram_vec, register_int_counter, register_int_counter_vec, register_int_gauge,
+    Encoder, HistogramVec, IntCounter, IntCounterVec, IntGauge, TextEncoder,
+};
+
+lazy_static! {
+    /// JSON-RPC calls, per method and status (`ok` or `error`).
+    pub static ref RPC_CALLS: IntCounterVec = register_int_counter_vec!(
+        "era_test_node_rpc_calls_total",
+        "JSON-RPC calls, per method and status",
+        &["method", "status"]
+    )
+    .unwrap();
+    /// JSON-RPC call latency, per method.
+    pub static ref RPC_CALL_DURATION: HistogramVec = register_histogram_vec!(
+        "era_test_node_rpc_call_duration_seconds",
+        "JSON-RPC call latency, per method",
+        &["method"]
+    )
+    .unwrap();
+    /// Executed transactions, per result (`success`, `reverted`, or `halted` for the rejected ones).
+    pub static ref TRANSACTIONS: IntCounterVec = register_int_counter_vec!(
+        "era_test_node_transactions_total",
+        "Executed transactions, per result",
+        &["result"]
+    )
+    .unwrap();
+    /// Gas used by the transactions sealed into blocks.
+    pub static ref GAS_USED: IntCounter = register_int_counter!(
+        "era_test_node_gas_used_total",
+        "Gas used by the transactions sealed into blocks"
+    )
+    .unwrap();
+    /// Requests sent to the fork source, per method.
+    pub static ref FORK_REQUESTS: IntCounterVec = register_int_counter_vec!(
+        "era_test_node_fork_requests_total",
+        "Requests sent to the fork source, per method",
+        &["method"]
+    )
+    .unwrap();
+    /// Lookups in the fork source cache, per kind of data and result (`hit` or `miss`).
+    pub static ref CACHE_LOOKUPS: IntCounterVec = register_int_counter_vec!(
+        "era_test_node_cache_lookups_total",
+        "Lookups in the fork source cache, per kind of data and result",
+        &["kind", "result"]
+    )
+    .unwrap();
+    /// Snapshots currently held, created via `evm_snapshot` or `anvil_snapshot`.
+    pub static ref SNAPSHOTS: IntGauge = register_int_gauge!(
+        "era_test_node_snapshots",
+        "Snapshots currently held"
+    )
+    .unwrap();
+}
+
+/// Records a JSON-RPC call, calls to unknown methods are grouped together.
+pub fn observe_rpc_call(method: &str, duration: Duration, output: Option<&Output>) {
+    let (method, status) = match output {
+        Some(Output::Failure(failure)) if failure.error.code == ErrorCode::MethodNotFound => {
+            ("unknown", "error")
+        }
+        Some(Output::Failure(_)) => (method, "error"),
+        _ => (method, "ok"),
+    };
+    RPC_CALLS.with_label_values(&[method, status]).inc();
+    RPC_CALL_DURATION
+        .with_label_values(&[method])
+        .observe(duration.as_secs_f64());
+}
+
+/// Records the result of a transaction, and the gas it used if it was sealed into a block.
+pub fn observe_transaction(result: &ExecutionResult, gas_used: u64) {
+    let label = match result {
+        ExecutionResult::Success { .. } => "success",
+        ExecutionResult::Revert { .. } => "reverted",
+        ExecutionResult::Halt { .. } => "halted",
+    };
+    TRANSACTIONS.with_label_values(&[label]).inc();
+    if !matches!(result, ExecutionResult::Halt { .. }) {
+        GAS_USED.inc_by(gas_used);
+    }
+}
+
+/// Records a lookup in the fork source cache, returning the looked up value.
+pub fn observe_cache_lookup<T>(kind: &'static str, value: Option<T>) -> Option<T> {
+    let result = if value.is_some() { "hit" } else { "miss" };
+    CACHE_LOOKUPS.with_label_values(&[kind, result]).inc();
+    value
+}
+
+/// Encodes all the registered metrics in the Prometheus text format.
+pub fn encode() -> Result<String, String> {
+    let mut buffer = vec![];
+    TextEncoder::new()
+        .encode(&prometheus::gather(), &mut buffer)
+        .map_err(|err| format!("failed encoding metrics: {}", err))?;
+    String::from_utf8(buffer).map_err(|err| format!("failed encoding metrics: {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_includes_observed_metrics() {
+        observe_cache_lookup::<()>("test", None);
+
+        let encoded = encode().expect("failed encoding metrics");
+
+        assert!(
+            encoded.contains("era_test_node_cache_lookups_total{kind=\"test\",result=\"miss\"}")
+        );
+    }
+}
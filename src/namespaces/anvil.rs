@@ -1,5 +1,5 @@
 use jsonrpc_derive::rpc;
-use zksync_basic_types::{Address, U256, U64};
+use zksync_basic_types::{web3::Bytes, Address, H256, U256, U64};
 
 use super::{ResetRequest, RpcResult};
 
@@ -33,7 +33,7 @@ pub trait AnvilNamespaceT {
 
     /// Sometimes you may want to advance the latest block number of the network by a large number of blocks.
     /// One way to do this would be to call the evm_mine RPC method multiple times, but this is too slow if you want to mine thousands of blocks.
-    /// The `anvil_mine` method can mine any number of blocks at once, in constant time. (It exhibits the same performance no matter how many blocks are mined.)
+    /// The `anvil_mine` method can mine any number of blocks at once, each of them at an exact timestamp, `interval` after the previous one.
     ///
     /// # Arguments
     ///
@@ -46,7 +46,35 @@ pub trait AnvilNamespaceT {
     #[rpc(name = "anvil_mine")]
     fn anvil_mine(&self, num_blocks: Option<U64>, interval: Option<U64>) -> RpcResult<bool>;
 
-    /// Reset the state of the network back to a fresh forked state, or disable forking.
+    /// Discards the latest local blocks, along with their transactions and state changes. Installed log filters
+    /// receive the logs of the discarded blocks again, with `removed` set to `true`.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - The number of blocks to discard, defaults to 1
+    ///
+    /// # Returns
+    ///
+    /// A `BoxFuture` containing a `Result` with a `bool` representing the success of the operation.
+    #[rpc(name = "anvil_rollback")]
+    fn rollback(&self, depth: Option<U64>) -> RpcResult<bool>;
+
+    /// Rolls the chain back to the given local block, discarding all the blocks after it along with their
+    /// transactions and state changes. Installed log filters receive the logs of the discarded blocks again,
+    /// with `removed` set to `true`.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_number` - The block to revert to, its state must still be archived (see `--archive`)
+    ///
+    /// # Returns
+    ///
+    /// A `BoxFuture` containing a `Result` with a `bool` representing the success of the operation.
+    #[rpc(name = "anvil_revertToBlock")]
+    fn revert_to_block(&self, block_number: U64) -> RpcResult<bool>;
+
+    /// Reset the state of the network back to a fresh forked state, or disable forking. It can also reset
+    /// the network to a previous local block, keeping the blocks and transactions up to it.
     ///
     /// # Arguments
     ///
@@ -112,4 +140,92 @@ pub trait AnvilNamespaceT {
     /// A `BoxFuture` containing a `Result` with a `bool` representing the success of the operation.
     #[rpc(name = "anvil_setStorageAt")]
     fn set_storage_at(&self, address: Address, slot: U256, value: U256) -> RpcResult<bool>;
+
+    /// Removes a transaction from the pending pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the pending transaction to remove
+    ///
+    /// # Returns
+    ///
+    /// A `BoxFuture` containing a `Result` with the hash of the removed transaction, or `null` if it was not pending.
+    #[rpc(name = "anvil_dropTransaction")]
+    fn drop_transaction(&self, hash: H256) -> RpcResult<Option<H256>>;
+
+    /// Removes all the transactions from the pending pool.
+    ///
+    /// # Returns
+    ///
+    /// A `BoxFuture` containing a `Result` representing the success of the operation.
+    #[rpc(name = "anvil_dropAllTransactions")]
+    fn drop_all_transactions(&self) -> RpcResult<()>;
+
+    /// Serializes the current state of the node, including the storage, blocks and transactions,
+    /// so it can be restored later with `anvil_loadState`.
+    ///
+    /// # Returns
+    ///
+    /// A `BoxFuture` containing a `Result` with the hex encoded state.
+    #[rpc(name = "anvil_dumpState")]
+    fn dump_state(&self) -> RpcResult<Bytes>;
+
+    /// Replaces the current state of the node with one previously returned by `anvil_dumpState`.
+    ///
+    /// # Arguments
+    ///
+    /// * `buf` - The hex encoded state
+    ///
+    /// # Returns
+    ///
+    /// A `BoxFuture` containing a `Result` with a `bool` representing the success of the operation.
+    #[rpc(name = "anvil_loadState")]
+    fn load_state(&self, buf: Bytes) -> RpcResult<bool>;
+
+    /// Creates a snapshot of the current state with the given name, replacing any previous snapshot with the same name.
+    /// Unlike `evm_snapshot`, it is not consumed when reverted to, and it is also written to the snapshot directory
+    /// (if configured via `--snapshot-dir`), so it survives restarts and can be shared across processes.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the snapshot, consisting of alphanumeric characters, `-` and `_`
+    ///
+    /// # Returns
+    ///
+    /// A `BoxFuture` containing a `Result` with a `bool` representing the success of the operation.
+    #[rpc(name = "anvil_snapshot")]
+    fn named_snapshot(&self, name: String) -> RpcResult<bool>;
+
+    /// Lists the names of the snapshots created with `anvil_snapshot`, including the ones in the snapshot directory.
+    ///
+    /// # Returns
+    ///
+    /// A `BoxFuture` containing a `Result` with the sorted names of the snapshots.
+    #[rpc(name = "anvil_listSnapshots")]
+    fn list_snapshots(&self) -> RpcResult<Vec<String>>;
+
+    /// Deletes the snapshot with the given name, both from memory and from the snapshot directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the snapshot
+    ///
+    /// # Returns
+    ///
+    /// A `BoxFuture` containing a `Result` with `true` if a snapshot was deleted, otherwise `false`.
+    #[rpc(name = "anvil_deleteSnapshot")]
+    fn delete_snapshot(&self, name: String) -> RpcResult<bool>;
+
+    /// Reverts the state of the node to the snapshot with the given name. The snapshot is kept, so it can be
+    /// reverted to again.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the snapshot
+    ///
+    /// # Returns
+    ///
+    /// A `BoxFuture` containing a `Result` with a `bool` representing the success of the operation.
+    #[rpc(name = "anvil_revertToSnapshot")]
+    fn revert_to_named_snapshot(&self, name: String) -> RpcResult<bool>;
 }
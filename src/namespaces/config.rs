@@ -1,3 +1,4 @@
+use crate::config::batch::SealCriteria;
 use crate::namespaces::Result;
 use crate::observability::LogLevel;
 use jsonrpc_derive::rpc;
@@ -25,6 +26,13 @@ pub trait ConfigurationApiNamespaceT {
     #[rpc(name = "config_getCurrentTimestamp", returns = "u64")]
     fn config_get_current_timestamp(&self) -> Result<u64>;
 
+    /// Get the InMemoryNodeInner's seal_criteria property
+    ///
+    /// # Returns
+    /// The current criteria used to decide when an L1 batch is sealed.
+    #[rpc(name = "config_getBatchSealCriteria", returns = "SealCriteria")]
+    fn config_get_batch_seal_criteria(&self) -> Result<SealCriteria>;
+
     /// Set show_calls for the InMemoryNodeInner
     ///
     /// # Parameters
@@ -85,6 +93,16 @@ pub trait ConfigurationApiNamespaceT {
     #[rpc(name = "config_setResolveHashes", returns = "bool")]
     fn config_set_resolve_hashes(&self, value: bool) -> Result<bool>;
 
+    /// Set seal_criteria for the InMemoryNodeInner
+    ///
+    /// # Parameters
+    /// - `value`: The criteria used to decide when an L1 batch is sealed. Omitted limits are set to their defaults
+    ///
+    /// # Returns
+    /// The updated `seal_criteria` value for the InMemoryNodeInner.
+    #[rpc(name = "config_setBatchSealCriteria", returns = "SealCriteria")]
+    fn config_set_batch_seal_criteria(&self, value: SealCriteria) -> Result<SealCriteria>;
+
     /// Set the logging for the InMemoryNodeInner
     ///
     /// # Parameters
@@ -0,0 +1,45 @@
+use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
+use zksync_web3_decl::types::{PubSubFilter, PubSubResult};
+
+use super::Result;
+
+///
+/// ETH namespace subscriptions, only available over WebSocket.
+///
+#[rpc]
+pub trait EthPubSubNamespaceT {
+    type Metadata;
+
+    /// Starts a subscription, notified with `eth_subscription` messages until it is cancelled
+    /// or the connection is closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `sub_type` - One of `newHeads`, `logs` or `newPendingTransactions`
+    /// * `params` - The addresses and topics to filter a `logs` subscription by
+    #[pubsub(subscription = "eth_subscription", subscribe, name = "eth_subscribe")]
+    fn subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<PubSubResult>,
+        sub_type: String,
+        params: Option<PubSubFilter>,
+    );
+
+    /// Cancels a subscription created with `eth_subscribe`.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the subscription
+    ///
+    /// # Returns
+    ///
+    /// A `Result` with `true` if the subscription was cancelled, otherwise `false`.
+    #[pubsub(
+        subscription = "eth_subscription",
+        unsubscribe,
+        name = "eth_unsubscribe"
+    )]
+    fn unsubscribe(&self, meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool>;
+}
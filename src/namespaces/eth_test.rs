@@ -1,7 +1,21 @@
+use ethers_core::types::transaction::eip712::TypedData;
 use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_derive::rpc;
-use zksync_basic_types::H256;
-use zksync_types::transaction_request::CallRequest;
+use serde::Deserialize;
+use zksync_basic_types::{web3::Bytes, Address, H256};
+use zksync_types::transaction_request::{CallRequest, Eip712Meta};
+
+/// A transaction passed to `eth_sendTransaction` or `eth_signTransaction`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendTransactionRequest {
+    #[serde(flatten)]
+    pub call: CallRequest,
+    /// The zkSync specific fields (paymaster params, factory deps and gas per pubdata), which make the
+    /// transaction an EIP-712 one.
+    #[serde(default)]
+    pub custom_data: Option<Eip712Meta>,
+}
 
 ///
 /// ETH namespace extension for the test node.
@@ -9,5 +23,24 @@ use zksync_types::transaction_request::CallRequest;
 #[rpc]
 pub trait EthTestNodeNamespaceT {
     #[rpc(name = "eth_sendTransaction")]
-    fn send_transaction(&self, tx: CallRequest) -> BoxFuture<Result<H256>>;
+    fn send_transaction(&self, tx: SendTransactionRequest) -> BoxFuture<Result<H256>>;
+
+    #[rpc(name = "eth_sendRawTransactionBundle")]
+    fn send_raw_transaction_bundle(&self, txs: Vec<Bytes>) -> BoxFuture<Result<Vec<H256>>>;
+
+    /// Signs an EIP-191 personal message on behalf of a dev account.
+    #[rpc(name = "eth_sign")]
+    fn sign(&self, address: Address, message: Bytes) -> BoxFuture<Result<Bytes>>;
+
+    /// Same as `eth_sign`, with the parameters in reverse order.
+    #[rpc(name = "personal_sign")]
+    fn personal_sign(&self, message: Bytes, address: Address) -> BoxFuture<Result<Bytes>>;
+
+    /// Signs EIP-712 typed data on behalf of a dev account.
+    #[rpc(name = "eth_signTypedData_v4")]
+    fn sign_typed_data(&self, address: Address, typed_data: TypedData) -> BoxFuture<Result<Bytes>>;
+
+    /// Signs a transaction on behalf of a dev account, without submitting it.
+    #[rpc(name = "eth_signTransaction")]
+    fn sign_transaction(&self, tx: SendTransactionRequest) -> BoxFuture<Result<Bytes>>;
 }
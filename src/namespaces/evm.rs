@@ -1,8 +1,28 @@
 use jsonrpc_derive::rpc;
-use zksync_basic_types::{Address, U256, U64};
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{web3::Bytes, Address, U256, U64};
 
 use crate::namespaces::RpcResult;
 
+/// Options for `evm_mine`, either a timestamp for the mined block or an options object.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MineOptions {
+    Timestamp(U64),
+    #[serde(rename_all = "camelCase")]
+    Options {
+        /// The exact timestamp of the first mined block.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timestamp: Option<U64>,
+        /// The number of blocks to mine, defaults to 1.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        blocks: Option<U64>,
+        /// The raw signed transactions to include in the first mined block, after the pending ones.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        transactions: Vec<Bytes>,
+    },
+}
+
 #[rpc]
 pub trait EvmNamespaceT {
     /// Increase the current timestamp for the node
@@ -30,12 +50,17 @@ pub trait EvmNamespaceT {
 
     /// Force a single block to be mined.
     ///
-    /// Will mine an empty block (containing zero transactions)
+    /// Will seal all the pending transactions, or mine an empty block (containing zero transactions)
+    /// if there are none.
+    ///
+    /// # Parameters
+    /// - `options`: Optionally, the exact timestamp of the block, or an object with the `timestamp` of the
+    ///   first block, the number of `blocks` to mine and raw `transactions` to include in the first block
     ///
     /// # Returns
     /// The string "0x0".
     #[rpc(name = "evm_mine")]
-    fn evm_mine(&self) -> RpcResult<String>;
+    fn evm_mine(&self, options: Option<MineOptions>) -> RpcResult<String>;
 
     /// Set timestamp for the next block. The timestamp must be in future.
     ///
@@ -80,4 +105,38 @@ pub trait EvmNamespaceT {
     /// `true` if a snapshot was reverted, otherwise `false`.
     #[rpc(name = "evm_revert")]
     fn revert_snapshot(&self, snapshot_id: U64) -> RpcResult<bool>;
+
+    /// Enables or disables automatic mining of new blocks with each new transaction submitted to the network.
+    /// While disabled, submitted transactions are kept pending until a block is mined, e.g. via `evm_mine`.
+    ///
+    /// # Parameters
+    /// - `enabled`: Whether automine should be enabled
+    ///
+    /// # Returns
+    /// `true` on success.
+    #[rpc(name = "evm_setAutomine")]
+    fn set_automine(&self, enabled: bool) -> RpcResult<bool>;
+
+    /// Enables (with a numeric argument greater than 0) or disables (with a numeric argument equal to 0)
+    /// the automatic mining of blocks at a regular interval of milliseconds.
+    /// Each block seals all the pending transactions, or is empty if there are none.
+    ///
+    /// # Parameters
+    /// - `interval_ms`: The block time in milliseconds
+    ///
+    /// # Returns
+    /// `true` on success.
+    #[rpc(name = "evm_setIntervalMining")]
+    fn set_interval_mining(&self, interval_ms: u64) -> RpcResult<bool>;
+
+    /// Sets the maximum gas used by all the transactions in a block. Transactions that would exceed it are
+    /// moved to the next block, while a transaction exceeding it on its own is rejected.
+    ///
+    /// # Parameters
+    /// - `gas_limit`: The new block gas limit
+    ///
+    /// # Returns
+    /// `true` on success.
+    #[rpc(name = "evm_setBlockGasLimit")]
+    fn set_block_gas_limit(&self, gas_limit: U64) -> RpcResult<bool>;
 }
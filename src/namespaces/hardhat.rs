@@ -1,6 +1,6 @@
 use jsonrpc_derive::rpc;
 use serde::{Deserialize, Serialize};
-use zksync_basic_types::{Address, U256, U64};
+use zksync_basic_types::{Address, H256, U256, U64};
 
 use super::RpcResult;
 
@@ -14,7 +14,9 @@ pub struct ResetRequestForking {
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
 pub struct ResetRequest {
-    /// The block number to reset the state to.
+    /// The block number to reset the state to. Local blocks keep the history up to them, other blocks are
+    /// forked again from the current fork source. Only the state of the last 128 local blocks is kept,
+    /// unless the node runs with `--archive`, so resetting to an older local block fails.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub to: Option<U64>,
     // Forking to a specified URL.
@@ -52,7 +54,7 @@ pub trait HardhatNamespaceT {
 
     /// Sometimes you may want to advance the latest block number of the network by a large number of blocks.
     /// One way to do this would be to call the evm_mine RPC method multiple times, but this is too slow if you want to mine thousands of blocks.
-    /// The hardhat_mine method can mine any number of blocks at once, in constant time. (It exhibits the same performance no matter how many blocks are mined.)
+    /// The hardhat_mine method can mine any number of blocks at once, each of them at an exact timestamp, `interval` after the previous one.
     ///
     /// # Arguments
     ///
@@ -65,7 +67,8 @@ pub trait HardhatNamespaceT {
     #[rpc(name = "hardhat_mine")]
     fn hardhat_mine(&self, num_blocks: Option<U64>, interval: Option<U64>) -> RpcResult<bool>;
 
-    /// Reset the state of the network back to a fresh forked state, or disable forking.
+    /// Reset the state of the network back to a fresh forked state, or disable forking. It can also reset
+    /// the network to a previous local block, keeping the blocks and transactions up to it.
     ///
     /// # Arguments
     ///
@@ -131,4 +134,24 @@ pub trait HardhatNamespaceT {
     /// A `BoxFuture` containing a `Result` with a `bool` representing the success of the operation.
     #[rpc(name = "hardhat_setStorageAt")]
     fn set_storage_at(&self, address: Address, slot: U256, value: U256) -> RpcResult<bool>;
+
+    /// Returns whether new blocks are automatically mined with each transaction submitted to the network.
+    ///
+    /// # Returns
+    ///
+    /// A `BoxFuture` containing a `Result` with `true` if automine is enabled, `false` otherwise.
+    #[rpc(name = "hardhat_getAutomine")]
+    fn get_automine(&self) -> RpcResult<bool>;
+
+    /// Removes a transaction from the pending pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the pending transaction to remove
+    ///
+    /// # Returns
+    ///
+    /// A `BoxFuture` containing a `Result` with `true` if the transaction was removed, `false` if it was not pending.
+    #[rpc(name = "hardhat_dropTransaction")]
+    fn drop_transaction(&self, hash: H256) -> RpcResult<bool>;
 }
@@ -2,10 +2,12 @@ mod anvil;
 mod config;
 mod debug;
 mod eth;
+mod eth_pubsub;
 mod eth_test;
 mod evm;
 mod hardhat;
 mod net;
+mod txpool;
 mod web3;
 mod zks;
 
@@ -13,10 +15,12 @@ pub use anvil::AnvilNamespaceT;
 pub use config::ConfigurationApiNamespaceT;
 pub use debug::DebugNamespaceT;
 pub use eth::EthNamespaceT;
-pub use eth_test::EthTestNodeNamespaceT;
-pub use evm::EvmNamespaceT;
+pub use eth_pubsub::EthPubSubNamespaceT;
+pub use eth_test::{EthTestNodeNamespaceT, SendTransactionRequest};
+pub use evm::{EvmNamespaceT, MineOptions};
 pub use hardhat::{HardhatNamespaceT, ResetRequest};
 pub use net::NetNamespaceT;
+pub use txpool::{TxpoolContent, TxpoolNamespaceT, TxpoolStatus};
 pub use web3::Web3NamespaceT;
 pub use zks::ZksNamespaceT;
 
@@ -0,0 +1,62 @@
+use std::collections::BTreeMap;
+
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{Address, U64};
+use zksync_types::api::Transaction;
+
+use crate::namespaces::RpcResult;
+
+/// The number of transactions in the pool, as returned by `txpool_status`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TxpoolStatus {
+    /// The number of transactions that can be included in the next block.
+    pub pending: U64,
+    /// The number of transactions waiting for a nonce gap to be filled.
+    pub queued: U64,
+}
+
+/// The transactions in the pool grouped by sender and nonce, as returned by `txpool_content` and `txpool_inspect`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TxpoolContent<T> {
+    /// The transactions that can be included in the next block.
+    pub pending: BTreeMap<Address, BTreeMap<String, T>>,
+    /// The transactions waiting for a nonce gap to be filled.
+    pub queued: BTreeMap<Address, BTreeMap<String, T>>,
+}
+
+impl<T> Default for TxpoolContent<T> {
+    fn default() -> Self {
+        Self {
+            pending: Default::default(),
+            queued: Default::default(),
+        }
+    }
+}
+
+#[rpc]
+pub trait TxpoolNamespaceT {
+    /// Returns the number of pending and queued transactions in the pool.
+    ///
+    /// # Returns
+    ///
+    /// A `BoxFuture` containing a `Result` with the number of pending and queued transactions.
+    #[rpc(name = "txpool_status")]
+    fn txpool_status(&self) -> RpcResult<TxpoolStatus>;
+
+    /// Returns the details of all the pending and queued transactions in the pool, grouped by sender and nonce.
+    ///
+    /// # Returns
+    ///
+    /// A `BoxFuture` containing a `Result` with the pending and queued transactions.
+    #[rpc(name = "txpool_content")]
+    fn txpool_content(&self) -> RpcResult<TxpoolContent<Transaction>>;
+
+    /// Returns a textual summary of all the pending and queued transactions in the pool, grouped by sender and nonce.
+    ///
+    /// # Returns
+    ///
+    /// A `BoxFuture` containing a `Result` with the summaries of the pending and queued transactions.
+    #[rpc(name = "txpool_inspect")]
+    fn txpool_inspect(&self) -> RpcResult<TxpoolContent<String>>;
+}
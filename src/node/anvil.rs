@@ -1,4 +1,4 @@
-use zksync_basic_types::{Address, U256, U64};
+use zksync_basic_types::{web3::Bytes, Address, H256, U256, U64};
 use zksync_web3_decl::error::Web3Error;
 
 use crate::{
@@ -38,6 +38,24 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> AnvilNames
             .into_boxed_future()
     }
 
+    fn rollback(&self, depth: Option<U64>) -> RpcResult<bool> {
+        self.rollback(depth)
+            .map_err(|err| {
+                tracing::error!("failed rolling back: {:?}", err);
+                into_jsrpc_error_message(err.to_string())
+            })
+            .into_boxed_future()
+    }
+
+    fn revert_to_block(&self, block_number: U64) -> RpcResult<bool> {
+        self.revert_to_block(block_number)
+            .map_err(|err| {
+                tracing::error!("failed reverting to block: {:?}", err);
+                into_jsrpc_error_message(err.to_string())
+            })
+            .into_boxed_future()
+    }
+
     fn reset_network(&self, reset_spec: Option<ResetRequest>) -> RpcResult<bool> {
         self.reset_network(reset_spec)
             .map_err(|err| {
@@ -82,4 +100,76 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> AnvilNames
             })
             .into_boxed_future()
     }
+
+    fn drop_transaction(&self, hash: H256) -> RpcResult<Option<H256>> {
+        self.drop_transaction(hash)
+            .map_err(|err| {
+                tracing::error!("failed dropping transaction: {:?}", err);
+                into_jsrpc_error(Web3Error::InternalError(err))
+            })
+            .into_boxed_future()
+    }
+
+    fn drop_all_transactions(&self) -> RpcResult<()> {
+        self.drop_all_transactions()
+            .map_err(|err| {
+                tracing::error!("failed dropping all transactions: {:?}", err);
+                into_jsrpc_error(Web3Error::InternalError(err))
+            })
+            .into_boxed_future()
+    }
+
+    fn dump_state(&self) -> RpcResult<Bytes> {
+        self.dump_state()
+            .map_err(|err| {
+                tracing::error!("failed dumping state: {:?}", err);
+                into_jsrpc_error(Web3Error::InternalError(err))
+            })
+            .into_boxed_future()
+    }
+
+    fn load_state(&self, buf: Bytes) -> RpcResult<bool> {
+        self.load_state(buf)
+            .map_err(|err| {
+                tracing::error!("failed loading state: {:?}", err);
+                into_jsrpc_error(Web3Error::InternalError(err))
+            })
+            .into_boxed_future()
+    }
+
+    fn named_snapshot(&self, name: String) -> RpcResult<bool> {
+        self.named_snapshot(name)
+            .map_err(|err| {
+                tracing::error!("failed creating snapshot: {:?}", err);
+                into_jsrpc_error(Web3Error::InternalError(err))
+            })
+            .into_boxed_future()
+    }
+
+    fn list_snapshots(&self) -> RpcResult<Vec<String>> {
+        self.list_snapshots()
+            .map_err(|err| {
+                tracing::error!("failed listing snapshots: {:?}", err);
+                into_jsrpc_error(Web3Error::InternalError(err))
+            })
+            .into_boxed_future()
+    }
+
+    fn delete_snapshot(&self, name: String) -> RpcResult<bool> {
+        self.delete_snapshot(name)
+            .map_err(|err| {
+                tracing::error!("failed deleting snapshot: {:?}", err);
+                into_jsrpc_error(Web3Error::InternalError(err))
+            })
+            .into_boxed_future()
+    }
+
+    fn revert_to_named_snapshot(&self, name: String) -> RpcResult<bool> {
+        self.revert_to_named_snapshot(name)
+            .map_err(|err| {
+                tracing::error!("failed reverting to snapshot: {:?}", err);
+                into_jsrpc_error(Web3Error::InternalError(err))
+            })
+            .into_boxed_future()
+    }
 }
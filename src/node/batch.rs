@@ -0,0 +1,98 @@
+use zksync_types::l2::L2Tx;
+
+use crate::config::batch::SealCriteria;
+
+/// Resources consumed by the transactions of an L1 batch, checked against the [SealCriteria].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BatchUsage {
+    pub transactions: usize,
+    pub gas: u64,
+    pub pubdata: u64,
+    pub bootloader_memory: usize,
+}
+
+impl BatchUsage {
+    /// Creates the usage of a single transaction.
+    pub fn for_tx(tx: &L2Tx, gas: u64, pubdata: u64) -> Self {
+        Self {
+            transactions: 1,
+            gas,
+            pubdata,
+            bootloader_memory: tx
+                .common_data
+                .input
+                .as_ref()
+                .map(|input| input.data.len())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Returns the combined usage of both `self` and `other`.
+    pub fn add(&self, other: &BatchUsage) -> Self {
+        Self {
+            transactions: self.transactions.saturating_add(other.transactions),
+            gas: self.gas.saturating_add(other.gas),
+            pubdata: self.pubdata.saturating_add(other.pubdata),
+            bootloader_memory: self
+                .bootloader_memory
+                .saturating_add(other.bootloader_memory),
+        }
+    }
+
+    /// Returns the name of the first limit exceeded by this usage, if any. The limits are the
+    /// `block_gas_limit` and the ones of the batch seal `criteria`.
+    pub fn exceeded_limit(
+        &self,
+        criteria: &SealCriteria,
+        block_gas_limit: u64,
+    ) -> Option<&'static str> {
+        if self.gas > block_gas_limit {
+            Some("block gas")
+        } else if self.transactions > criteria.max_transactions {
+            Some("batch transactions")
+        } else if self.gas > criteria.max_gas {
+            Some("batch gas")
+        } else if self.pubdata > criteria.max_pubdata {
+            Some("batch published pubdata")
+        } else if self.bootloader_memory > criteria.max_bootloader_memory {
+            Some("batch bootloader memory")
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exceeded_limit_reports_first_exceeded_limit() {
+        let criteria = SealCriteria {
+            max_transactions: 2,
+            max_gas: 100,
+            max_pubdata: 10,
+            max_bootloader_memory: 1_000,
+        };
+        let usage = BatchUsage {
+            transactions: 1,
+            gas: 60,
+            pubdata: 6,
+            bootloader_memory: 10,
+        };
+
+        assert_eq!(None, usage.exceeded_limit(&criteria, 1_000));
+        assert_eq!(
+            Some("batch gas"),
+            usage.add(&usage).exceeded_limit(&criteria, 1_000)
+        );
+        assert_eq!(
+            Some("batch transactions"),
+            usage
+                .add(&usage)
+                .add(&usage)
+                .exceeded_limit(&criteria, 1_000)
+        );
+        assert_eq!(Some("block gas"), usage.exceeded_limit(&criteria, 50));
+    }
+}
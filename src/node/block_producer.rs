@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::{fork::ForkSource, node::InMemoryNode};
+
+/// How often to check whether interval mining was enabled, while it is disabled.
+const IDLE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Background task that mines a new block every `block_time` milliseconds when interval mining is enabled.
+///
+/// Each block seals all the transactions from the pending pool, or is empty if there are none.
+/// The interval is read from [crate::config::node::InMemoryNodeConfig::block_time] on every iteration,
+/// so it can be changed at runtime via `evm_setIntervalMining`.
+pub struct BlockProducer<S: Clone> {
+    node: InMemoryNode<S>,
+}
+
+impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> BlockProducer<S> {
+    pub fn new(node: InMemoryNode<S>) -> Self {
+        Self { node }
+    }
+
+    /// Runs the block producer until the process exits.
+    pub async fn run(self) {
+        let mut next_block_at: Option<Instant> = None;
+        loop {
+            let block_time = match self.node.get_inner().read() {
+                Ok(reader) => reader
+                    .config
+                    .block_time
+                    .filter(|block_time| *block_time > 0),
+                Err(err) => {
+                    tracing::error!("failed acquiring lock: {:?}", err);
+                    None
+                }
+            };
+
+            let block_time = match block_time {
+                Some(block_time) => Duration::from_millis(block_time),
+                None => {
+                    next_block_at = None;
+                    tokio::time::sleep(IDLE_POLL_INTERVAL).await;
+                    continue;
+                }
+            };
+
+            let deadline = *next_block_at.get_or_insert_with(|| Instant::now() + block_time);
+            tokio::time::sleep_until(deadline).await;
+
+            if let Err(err) = self.node.mine_block() {
+                tracing::error!("failed mining block: {:?}", err);
+            }
+
+            // keep a steady cadence independent of how long mining takes, but skip the missed
+            // blocks if mining took longer than the block time
+            next_block_at = Some((deadline + block_time).max(Instant::now()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{http_fork_source::HttpForkSource, namespaces::EthNamespaceT};
+
+    #[tokio::test]
+    async fn test_block_producer_mines_blocks_when_interval_mining_enabled() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let start_block = node
+            .get_block_number()
+            .await
+            .expect("failed fetching block number");
+
+        let producer = tokio::spawn(BlockProducer::new(node.clone()).run());
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(
+            start_block,
+            node.get_block_number()
+                .await
+                .expect("failed fetching block number"),
+            "no blocks must be mined while interval mining is disabled"
+        );
+
+        node.set_interval_mining(10)
+            .expect("failed enabling interval mining");
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        producer.abort();
+
+        let current_block = node
+            .get_block_number()
+            .await
+            .expect("failed fetching block number");
+        assert!(current_block > start_block);
+    }
+}
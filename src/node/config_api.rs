@@ -1,12 +1,15 @@
 use zksync_web3_decl::error::Web3Error;
 
 use crate::{
-    config::node::{ShowCalls, ShowGasDetails, ShowStorageLogs, ShowVMDetails},
+    config::{
+        batch::SealCriteria,
+        node::{ShowCalls, ShowGasDetails, ShowStorageLogs, ShowVMDetails},
+    },
     fork::ForkSource,
     namespaces::{ConfigurationApiNamespaceT, Result},
     node::InMemoryNode,
     observability::LogLevel,
-    utils::into_jsrpc_error,
+    utils::{into_jsrpc_error, into_jsrpc_error_message},
 };
 
 impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> ConfigurationApiNamespaceT
@@ -48,6 +51,18 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> Configurat
             .map(|reader| reader.current_timestamp)
     }
 
+    fn config_get_batch_seal_criteria(&self) -> Result<SealCriteria> {
+        self.get_inner()
+            .read()
+            .map_err(|err| {
+                tracing::error!("failed acquiring lock: {:?}", err);
+                into_jsrpc_error(Web3Error::InternalError(anyhow::Error::msg(
+                    "Failed to acquire read lock for inner node state.",
+                )))
+            })
+            .map(|reader| reader.config.seal_criteria)
+    }
+
     fn config_set_show_calls(&self, value: String) -> Result<String> {
         let show_calls = match value.parse::<ShowCalls>() {
             Ok(value) => value,
@@ -191,6 +206,22 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> Configurat
             })
     }
 
+    fn config_set_batch_seal_criteria(&self, value: SealCriteria) -> Result<SealCriteria> {
+        value.validate().map_err(into_jsrpc_error_message)?;
+        self.get_inner()
+            .write()
+            .map_err(|err| {
+                tracing::error!("failed acquiring lock: {:?}", err);
+                into_jsrpc_error(Web3Error::InternalError(anyhow::Error::msg(
+                    "Failed to acquire write lock for inner node state.",
+                )))
+            })
+            .map(|mut writer| {
+                writer.config.seal_criteria = value;
+                writer.config.seal_criteria
+            })
+    }
+
     fn config_set_log_level(&self, level: LogLevel) -> Result<bool> {
         if let Some(observability) = &self
             .get_inner()
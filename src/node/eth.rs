@@ -1,13 +1,14 @@
 use std::collections::HashSet;
 
 use colored::Colorize;
+use ethers_core::types::transaction::eip712::{Eip712, TypedData};
 use futures::FutureExt;
 use itertools::Itertools;
-use multivm::interface::{ExecutionResult, TxExecutionMode};
+use multivm::interface::ExecutionResult;
 use multivm::vm_latest::constants::ETH_CALL_GAS_LIMIT;
 use zksync_basic_types::{
     web3::{self, Bytes},
-    AccountTreeId, Address, H160, H256, U256, U64,
+    AccountTreeId, Address, L2ChainId, H160, H256, U256, U64,
 };
 use zksync_types::{
     api::{Block, BlockIdVariant, BlockNumber, TransactionVariant},
@@ -26,9 +27,11 @@ use zksync_web3_decl::{
 
 use crate::{
     filters::{FilterType, LogFilter},
-    fork::ForkSource,
-    namespaces::{EthNamespaceT, EthTestNodeNamespaceT, RpcResult},
-    node::{InMemoryNode, TransactionResult, MAX_TX_SIZE, PROTOCOL_VERSION},
+    fork::{ForkSource, HistoricalStorage},
+    namespaces::{EthNamespaceT, EthTestNodeNamespaceT, RpcResult, SendTransactionRequest},
+    node::{
+        pool::to_api_transaction, InMemoryNode, TransactionResult, MAX_TX_SIZE, PROTOCOL_VERSION,
+    },
     utils::{
         self, h256_to_u64, into_jsrpc_error, into_jsrpc_error_message, not_implemented,
         report_into_jsrpc_error, IntoBoxedFuture,
@@ -54,7 +57,7 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EthNamespa
     /// # Arguments
     ///
     /// * `req` - The call request containing the function name and arguments.
-    /// * `_block` - The block ID variant (unused).
+    /// * `block` - The block ID variant (optional), the call is executed on top of the state of that block.
     ///
     /// # Returns
     ///
@@ -62,12 +65,12 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EthNamespa
     fn call(
         &self,
         req: zksync_types::transaction_request::CallRequest,
-        _block: Option<BlockIdVariant>,
+        block: Option<BlockIdVariant>,
     ) -> RpcResult<Bytes> {
         match L2Tx::from_request(req.into(), MAX_TX_SIZE) {
             Ok(mut tx) => {
                 tx.common_data.fee.gas_limit = ETH_CALL_GAS_LIMIT.into();
-                let result = self.run_l2_call(tx);
+                let result = self.run_l2_call(tx, block);
 
                 match result {
                     Ok(execution_result) => match execution_result {
@@ -124,12 +127,12 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EthNamespa
     /// # Arguments
     ///
     /// * `address` - The address to get the balance of.
-    /// * `_block` - The block ID variant (optional).
+    /// * `block` - The block ID variant (optional).
     ///
     /// # Returns
     ///
     /// A `BoxFuture` that resolves to a `Result` containing the balance of the specified address as a `U256` or a `jsonrpc_core::Error` if an error occurred.
-    fn get_balance(&self, address: Address, _block: Option<BlockIdVariant>) -> RpcResult<U256> {
+    fn get_balance(&self, address: Address, block: Option<BlockIdVariant>) -> RpcResult<U256> {
         let inner = self.get_inner().clone();
 
         Box::pin(async move {
@@ -140,7 +143,12 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EthNamespa
 
             match inner.write() {
                 Ok(inner_guard) => {
-                    match inner_guard.fork_storage.read_value_internal(&balance_key) {
+                    let state = inner_guard
+                        .archived_state_at(block)
+                        .map_err(into_jsrpc_error_message)?;
+                    match HistoricalStorage::new(&inner_guard.fork_storage, state)
+                        .read_value_internal(&balance_key)
+                    {
                         Ok(balance) => Ok(h256_to_u256(balance)),
                         Err(error) => Err(report_into_jsrpc_error(error)),
                     }
@@ -246,7 +254,7 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EthNamespa
     /// # Arguments
     ///
     /// * `address` - The address to retrieve the code from.
-    /// * `_block` - An optional block ID variant.
+    /// * `block` - An optional block ID variant.
     ///
     /// # Returns
     ///
@@ -255,7 +263,7 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EthNamespa
     fn get_code(
         &self,
         address: zksync_basic_types::Address,
-        _block: Option<BlockIdVariant>,
+        block: Option<BlockIdVariant>,
     ) -> RpcResult<Bytes> {
         let inner = self.get_inner().clone();
 
@@ -263,18 +271,22 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EthNamespa
             let code_key = get_code_key(&address);
 
             match inner.write() {
-                Ok(guard) => match guard.fork_storage.read_value_internal(&code_key) {
-                    Ok(code_hash) => {
-                        match guard.fork_storage.load_factory_dep_internal(code_hash) {
+                Ok(guard) => {
+                    let state = guard
+                        .archived_state_at(block)
+                        .map_err(into_jsrpc_error_message)?;
+                    let storage = HistoricalStorage::new(&guard.fork_storage, state);
+                    match storage.read_value_internal(&code_key) {
+                        Ok(code_hash) => match storage.load_factory_dep_internal(code_hash) {
                             Ok(raw_code) => {
                                 let code = raw_code.unwrap_or_default();
                                 Ok(Bytes::from(code))
                             }
                             Err(error) => Err(report_into_jsrpc_error(error)),
-                        }
+                        },
+                        Err(error) => Err(report_into_jsrpc_error(error)),
                     }
-                    Err(error) => Err(report_into_jsrpc_error(error)),
-                },
+                }
                 Err(_) => Err(into_jsrpc_error(Web3Error::InternalError(
                     anyhow::Error::msg("Failed to acquire write lock for code retrieval"),
                 ))),
@@ -287,7 +299,7 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EthNamespa
     /// # Arguments
     ///
     /// * `address` - The address to get the transaction count for.
-    /// * `_block` - Optional block ID variant.
+    /// * `block` - Optional block ID variant. For the `pending` block, transactions waiting in the pending pool are counted as well.
     ///
     /// # Returns
     ///
@@ -295,18 +307,43 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EthNamespa
     fn get_transaction_count(
         &self,
         address: zksync_basic_types::Address,
-        _block: Option<BlockIdVariant>,
+        block: Option<BlockIdVariant>,
     ) -> RpcResult<U256> {
         let inner = self.get_inner().clone();
 
         Box::pin(async move {
             let nonce_key = get_nonce_key(&address);
+            let include_pending = matches!(
+                block,
+                Some(BlockIdVariant::BlockNumber(BlockNumber::Pending))
+            );
 
             match inner.write() {
-                Ok(guard) => match guard.fork_storage.read_value_internal(&nonce_key) {
-                    Ok(result) => Ok(h256_to_u64(result).into()),
-                    Err(error) => Err(report_into_jsrpc_error(error)),
-                },
+                Ok(guard) => {
+                    let state = guard
+                        .archived_state_at(block)
+                        .map_err(into_jsrpc_error_message)?;
+                    match HistoricalStorage::new(&guard.fork_storage, state)
+                        .read_value_internal(&nonce_key)
+                    {
+                        Ok(result) => {
+                            let mut nonce = h256_to_u64(result);
+                            if include_pending {
+                                // account for the transactions still waiting in the pending pool
+                                if let Some(max_pending) = guard
+                                    .pool
+                                    .txs_from(address)
+                                    .map(|tx| tx.common_data.nonce.0 as u64)
+                                    .max()
+                                {
+                                    nonce = nonce.max(max_pending + 1);
+                                }
+                            }
+                            Ok(nonce.into())
+                        }
+                        Err(error) => Err(report_into_jsrpc_error(error)),
+                    }
+                }
                 Err(_) => Err(into_jsrpc_error(Web3Error::InternalError(
                     anyhow::Error::msg("Failed to acquire write lock for nonce retrieval"),
                 ))),
@@ -369,32 +406,13 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EthNamespa
             }
         };
 
-        let (tx_req, hash) = match TransactionRequest::from_bytes(&tx_bytes.0, chain_id) {
-            Ok(result) => result,
-            Err(e) => {
-                return futures::future::err(into_jsrpc_error(Web3Error::SerializationError(e)))
-                    .boxed()
-            }
-        };
-
-        let mut l2_tx: L2Tx = match L2Tx::from_request(tx_req, MAX_TX_SIZE) {
-            Ok(tx) => tx,
-            Err(e) => {
-                return futures::future::err(into_jsrpc_error(Web3Error::SerializationError(e)))
-                    .boxed()
-            }
+        let l2_tx = match decode_raw_transaction(tx_bytes, chain_id) {
+            Ok(l2_tx) => l2_tx,
+            Err(e) => return futures::future::err(into_jsrpc_error(e)).boxed(),
         };
 
-        l2_tx.set_input(tx_bytes.0, hash);
-        if hash != l2_tx.hash() {
-            let error_message =
-                "Invalid transaction data: computed hash does not match the provided hash.";
-            let web3_error = Web3Error::InternalError(anyhow::Error::msg(error_message));
-            return futures::future::err(into_jsrpc_error(web3_error)).boxed();
-        };
-
-        match self.run_l2_tx(l2_tx.clone(), TxExecutionMode::VerifyExecute) {
-            Ok(_) => Ok(hash).into_boxed_future(),
+        match self.submit_l2_tx(l2_tx.clone()) {
+            Ok(_) => Ok(l2_tx.hash()).into_boxed_future(),
             Err(e) => {
                 let error_message = format!("Execution error: {}", e);
                 futures::future::err(into_jsrpc_error(Web3Error::SubmitTransactionError(
@@ -507,15 +525,15 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EthNamespa
 
             let maybe_result = {
                 // try retrieving transaction from memory, and if unavailable subsequently from the fork
-                reader.tx_results.get(&hash).and_then(|TransactionResult { info, .. }| {
+                reader.tx_results.get(&hash).and_then(|TransactionResult { info, receipt, .. }| {
                     let input_data = info.tx.common_data.input.clone().or(None)?;
                     let chain_id = info.tx.common_data.extract_chain_id().or(None)?;
                     Some(zksync_types::api::Transaction {
                         hash,
                         nonce: U256::from(info.tx.common_data.nonce.0),
-                        block_hash: Some(hash),
+                        block_hash: Some(receipt.block_hash),
                         block_number: Some(U64::from(info.miniblock_number)),
-                        transaction_index: Some(U64::from(0)),
+                        transaction_index: Some(receipt.transaction_index),
                         from: Some(info.tx.initiator_account()),
                         to: Some(info.tx.recipient_account()),
                         value: info.tx.execute.value,
@@ -544,8 +562,11 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EthNamespa
                         ),
                         chain_id: U256::from(chain_id),
                         l1_batch_number: Some(U64::from(info.batch_number as u64)),
-                        l1_batch_tx_index: None,
+                        l1_batch_tx_index: receipt.l1_batch_tx_index,
                     })
+                }).or_else(|| {
+                    // transactions waiting in the pending pool are not yet part of any block
+                    reader.pool.get(&hash).map(to_api_transaction)
                 }).or_else(|| {
                     reader
                         .fork_storage
@@ -1069,21 +1090,14 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EthNamespa
                     Err(error) => Err(report_into_jsrpc_error(error)),
                 }
             } else if writer.block_hashes.contains_key(&block_number.as_u64()) {
-                let value = writer
-                    .block_hashes
-                    .get(&block_number.as_u64())
-                    .and_then(|block_hash| writer.previous_states.get(block_hash))
-                    .and_then(|state| state.get(&storage_key))
-                    .cloned()
-                    .unwrap_or_default();
-
-                if value.is_zero() {
-                    match writer.fork_storage.read_value_internal(&storage_key) {
-                        Ok(value) => Ok(H256(value.0)),
-                        Err(error) => Err(report_into_jsrpc_error(error)),
-                    }
-                } else {
-                    Ok(value)
+                let state = writer
+                    .archived_state_at(block)
+                    .map_err(into_jsrpc_error_message)?;
+                match HistoricalStorage::new(&writer.fork_storage, state)
+                    .read_value_internal(&storage_key)
+                {
+                    Ok(value) => Ok(H256(value.0)),
+                    Err(error) => Err(report_into_jsrpc_error(error)),
                 }
             } else {
                 writer
@@ -1377,29 +1391,189 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EthNamespa
 impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EthTestNodeNamespaceT
     for InMemoryNode<S>
 {
-    /// Sends a transaction to the L2 network. Can be used for the impersonated account.
+    /// Sends a transaction to the L2 network, from a dev account or an impersonated account.
+    ///
+    /// Transactions from the dev accounts are signed with their private keys, so they are validated like the ones
+    /// sent via `eth_sendRawTransaction`. Transactions from the impersonated accounts are left unsigned.
     ///
     /// # Arguments
     ///
-    /// * `tx` - A `CallRequest` struct representing the transaction.
+    /// * `tx` - The transaction, with the zkSync specific fields in its optional `customData`.
     ///
     /// # Returns
     ///
     /// A future that resolves to the hash of the transaction if successful, or an error if the transaction is invalid or execution fails.
-    fn send_transaction(
+    fn send_transaction(&self, tx: SendTransactionRequest) -> RpcResult<H256> {
+        let tx_req = match self.transaction_request(tx) {
+            Ok(tx_req) => tx_req,
+            Err(e) => return futures::future::err(e).boxed(),
+        };
+
+        let from = tx_req.from.unwrap_or_default();
+        let l2_tx = if self.dev_account_private_key(from).is_ok() {
+            self.signed_l2_tx(from, &tx_req)
+        } else {
+            self.impersonated_l2_tx(tx_req)
+        };
+        let l2_tx = match l2_tx {
+            Ok(l2_tx) => l2_tx,
+            Err(e) => return futures::future::err(e).boxed(),
+        };
+
+        match self.submit_l2_tx(l2_tx.clone()) {
+            Ok(_) => Ok(l2_tx.hash()).into_boxed_future(),
+            Err(e) => {
+                let error_message = format!("Execution error: {}", e);
+                futures::future::err(into_jsrpc_error_message(error_message)).boxed()
+            }
+        }
+    }
+
+    /// Executes a bundle of raw transactions atomically in a single new block.
+    ///
+    /// # Arguments
+    ///
+    /// * `txs` - The raw transactions of the bundle, in execution order.
+    ///
+    /// # Returns
+    ///
+    /// A future that resolves to the hashes of the transactions if all of them succeeded, or an error if any
+    /// of them is invalid or fails, in which case none of them is included.
+    fn send_raw_transaction_bundle(&self, txs: Vec<Bytes>) -> RpcResult<Vec<H256>> {
+        let chain_id = match self.get_inner().read() {
+            Ok(reader) => reader.fork_storage.chain_id,
+            Err(_) => {
+                return futures::future::err(into_jsrpc_error(Web3Error::InternalError(
+                    anyhow::Error::msg("Failed to acquire read lock for chain ID retrieval"),
+                )))
+                .boxed()
+            }
+        };
+
+        let l2_txs = match txs
+            .into_iter()
+            .map(|tx_bytes| decode_raw_transaction(tx_bytes, chain_id))
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(l2_txs) => l2_txs,
+            Err(e) => return futures::future::err(into_jsrpc_error(e)).boxed(),
+        };
+
+        match self.run_l2_tx_bundle(l2_txs) {
+            Ok(hashes) => Ok(hashes).into_boxed_future(),
+            Err(e) => {
+                let error_message = format!("Bundle execution error: {}", e);
+                futures::future::err(into_jsrpc_error_message(error_message)).boxed()
+            }
+        }
+    }
+    /// Signs an EIP-191 personal message on behalf of a dev account.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The dev account to sign with.
+    /// * `message` - The message to sign, which is prefixed with `"\x19Ethereum Signed Message:\n" + len(message)`.
+    ///
+    /// # Returns
+    ///
+    /// A future that resolves to the 65 bytes signature, or an error if the node doesn't know the key of the account.
+    fn sign(&self, address: Address, message: Bytes) -> RpcResult<Bytes> {
+        match self.sign_message(address, &message.0) {
+            Ok(signature) => Ok(Bytes(signature.serialize_packed().to_vec())).into_boxed_future(),
+            Err(e) => futures::future::err(into_jsrpc_error_message(e)).boxed(),
+        }
+    }
+
+    /// Same as [EthTestNodeNamespaceT::sign], with the parameters in reverse order.
+    fn personal_sign(&self, message: Bytes, address: Address) -> RpcResult<Bytes> {
+        EthTestNodeNamespaceT::sign(self, address, message)
+    }
+
+    /// Signs EIP-712 typed data on behalf of a dev account.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The dev account to sign with.
+    /// * `typed_data` - The typed data to sign, either as an object or as its JSON string.
+    ///
+    /// # Returns
+    ///
+    /// A future that resolves to the 65 bytes signature, or an error if the typed data is invalid or the node
+    /// doesn't know the key of the account.
+    fn sign_typed_data(&self, address: Address, typed_data: TypedData) -> RpcResult<Bytes> {
+        let hash = match typed_data.encode_eip712() {
+            Ok(hash) => H256(hash),
+            Err(e) => {
+                let error_message = format!("Invalid typed data: {}", e);
+                return futures::future::err(into_jsrpc_error_message(error_message)).boxed();
+            }
+        };
+
+        match self.sign_hash(address, hash) {
+            Ok(signature) => Ok(Bytes(signature.serialize_packed().to_vec())).into_boxed_future(),
+            Err(e) => futures::future::err(into_jsrpc_error_message(e)).boxed(),
+        }
+    }
+
+    /// Signs a transaction on behalf of a dev account, so it can be submitted via `eth_sendRawTransaction`.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction to sign, sent from a dev account. Its nonce defaults to the next one of the account.
+    ///
+    /// # Returns
+    ///
+    /// A future that resolves to the raw signed transaction.
+    fn sign_transaction(&self, tx: SendTransactionRequest) -> RpcResult<Bytes> {
+        let Some(from) = tx.call.from else {
+            return futures::future::err(into_jsrpc_error_message(
+                "Transaction is missing the `from` address".to_string(),
+            ))
+            .boxed();
+        };
+
+        let tx_req = match self.transaction_request(tx) {
+            Ok(tx_req) => tx_req,
+            Err(e) => return futures::future::err(e).boxed(),
+        };
+
+        match self.sign_transaction_request(from, &tx_req) {
+            Ok(bytes) => Ok(Bytes(bytes)).into_boxed_future(),
+            Err(e) => futures::future::err(e).boxed(),
+        }
+    }
+}
+
+impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> InMemoryNode<S> {
+    /// Builds the request of a transaction passed to `eth_sendTransaction` or `eth_signTransaction`,
+    /// filling in the chain ID, the nonce of the sender and the defaults of the gas fields.
+    fn transaction_request(
         &self,
-        tx: zksync_types::transaction_request::CallRequest,
-    ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<zksync_basic_types::H256>> {
-        let (chain_id, l1_gas_price) = match self.get_inner().read() {
+        tx: SendTransactionRequest,
+    ) -> Result<TransactionRequest, jsonrpc_core::Error> {
+        let SendTransactionRequest {
+            call: mut tx,
+            custom_data,
+        } = tx;
+        if custom_data.is_some() {
+            tx.eip712_meta = custom_data;
+        }
+
+        let (chain_id, l1_gas_price, next_nonce) = match self.get_inner().read() {
             Ok(reader) => (
                 reader.fork_storage.chain_id,
                 reader.fee_input_provider.l1_gas_price,
+                match (tx.nonce, tx.from) {
+                    (None, Some(from)) => {
+                        Some(reader.next_nonce(from).map_err(into_jsrpc_error_message)?)
+                    }
+                    _ => None,
+                },
             ),
             Err(_) => {
-                return futures::future::err(into_jsrpc_error_message(
+                return Err(into_jsrpc_error_message(
                     "Failed to acquire read lock for chain ID retrieval.".to_string(),
                 ))
-                .boxed()
             }
         };
 
@@ -1408,6 +1582,12 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EthTestNod
         if tx.gas.is_none() {
             tx_req.gas = U256::from(MAX_L1_TRANSACTION_GAS_LIMIT);
         }
+        if let Some(next_nonce) = next_nonce {
+            tx_req.nonce = U256::from(next_nonce.0);
+        }
+        if tx_req.eip712_meta.is_some() && tx_req.transaction_type.is_none() {
+            tx_req.transaction_type = Some(zksync_types::EIP_712_TX_TYPE.into());
+        }
 
         tx_req.chain_id = Some(chain_id.as_u64());
 
@@ -1416,8 +1596,7 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EthTestNod
             if tx.max_fee_per_gas.is_some() || tx.max_priority_fee_per_gas.is_some() {
                 let error_message = "Transaction contains unsupported fields: max_fee_per_gas or max_priority_fee_per_gas";
                 tracing::error!("{}", error_message);
-                return futures::future::err(into_jsrpc_error_message(error_message.to_string()))
-                    .boxed();
+                return Err(into_jsrpc_error_message(error_message.to_string()));
             }
         } else {
             tx_req.gas_price = tx.max_fee_per_gas.unwrap_or(U256::from(l1_gas_price));
@@ -1426,39 +1605,74 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EthTestNod
                 tx_req.transaction_type = Some(zksync_types::EIP_1559_TX_TYPE.into());
             }
         }
+
+        Ok(tx_req)
+    }
+
+    /// Signs the request on behalf of the dev account it's sent from. Returns the raw signed transaction.
+    fn sign_transaction_request(
+        &self,
+        from: Address,
+        tx_req: &TransactionRequest,
+    ) -> Result<Vec<u8>, jsonrpc_core::Error> {
+        let signed_message = tx_req
+            .get_default_signed_message()
+            .map_err(|e| into_jsrpc_error(Web3Error::SerializationError(e)))?;
+        let signature = self
+            .sign_hash(from, signed_message)
+            .map_err(into_jsrpc_error_message)?;
+        tx_req
+            .get_signed_bytes(&signature)
+            .map_err(|e| into_jsrpc_error(Web3Error::SerializationError(e)))
+    }
+
+    /// Builds a transaction sent from a dev account, signed with its private key.
+    fn signed_l2_tx(
+        &self,
+        from: Address,
+        tx_req: &TransactionRequest,
+    ) -> Result<L2Tx, jsonrpc_core::Error> {
+        let chain_id = match self.get_inner().read() {
+            Ok(reader) => reader.fork_storage.chain_id,
+            Err(_) => {
+                return Err(into_jsrpc_error_message(
+                    "Failed to acquire read lock for chain ID retrieval.".to_string(),
+                ))
+            }
+        };
+
+        let bytes = self.sign_transaction_request(from, tx_req)?;
+        decode_raw_transaction(Bytes(bytes), chain_id).map_err(into_jsrpc_error)
+    }
+
+    /// Builds an unsigned transaction sent from an impersonated account.
+    fn impersonated_l2_tx(
+        &self,
+        mut tx_req: TransactionRequest,
+    ) -> Result<L2Tx, jsonrpc_core::Error> {
         // Needed to calculate hash
         tx_req.r = Some(U256::default());
         tx_req.s = Some(U256::default());
         tx_req.v = Some(U64::from(27));
 
-        let hash = match tx_req.get_tx_hash() {
-            Ok(result) => result,
-            Err(e) => {
-                tracing::error!("Transaction request serialization error: {}", e);
-                return futures::future::err(into_jsrpc_error(Web3Error::SerializationError(e)))
-                    .boxed();
-            }
-        };
-        let bytes = match tx_req.get_signed_bytes(&PackedEthSignature::from_rsv(
-            &H256::default(),
-            &H256::default(),
-            27,
-        )) {
-            Ok(result) => result,
-            Err(e) => {
+        let hash = tx_req.get_tx_hash().map_err(|e| {
+            tracing::error!("Transaction request serialization error: {}", e);
+            into_jsrpc_error(Web3Error::SerializationError(e))
+        })?;
+        let bytes = tx_req
+            .get_signed_bytes(&PackedEthSignature::from_rsv(
+                &H256::default(),
+                &H256::default(),
+                27,
+            ))
+            .map_err(|e| {
                 tracing::error!("Transaction request serialization error: {}", e);
-                return futures::future::err(into_jsrpc_error(Web3Error::SerializationError(e)))
-                    .boxed();
-            }
-        };
-        let mut l2_tx: L2Tx = match L2Tx::from_request(tx_req, MAX_TX_SIZE) {
-            Ok(tx) => tx,
-            Err(e) => {
-                tracing::error!("Transaction serialization error: {}", e);
-                return futures::future::err(into_jsrpc_error(Web3Error::SerializationError(e)))
-                    .boxed();
-            }
-        };
+                into_jsrpc_error(Web3Error::SerializationError(e))
+            })?;
+        let mut l2_tx = L2Tx::from_request(tx_req, MAX_TX_SIZE).map_err(|e| {
+            tracing::error!("Transaction serialization error: {}", e);
+            into_jsrpc_error(Web3Error::SerializationError(e))
+        })?;
 
         // `v` was overwritten with 0 during converting into l2 tx
         let mut signature = vec![0u8; 65];
@@ -1478,31 +1692,49 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EthTestNod
                         l2_tx.common_data.initiator_address
                     );
                     tracing::error!("{}", error_message);
-                    return futures::future::err(into_jsrpc_error_message(error_message)).boxed();
+                    return Err(into_jsrpc_error_message(error_message));
                 }
             }
             Err(_) => {
-                return futures::future::err(into_jsrpc_error_message(
+                return Err(into_jsrpc_error_message(
                     "Failed to acquire read lock for accounts.".to_string(),
                 ))
-                .boxed()
             }
         }
 
-        match self.run_l2_tx(l2_tx.clone(), TxExecutionMode::VerifyExecute) {
-            Ok(_) => Ok(l2_tx.hash()).into_boxed_future(),
-            Err(e) => {
-                let error_message = format!("Execution error: {}", e);
-                futures::future::err(into_jsrpc_error_message(error_message)).boxed()
-            }
-        }
+        Ok(l2_tx)
     }
 }
 
+/// Decodes a raw signed transaction into an [L2Tx].
+pub(crate) fn decode_raw_transaction(
+    tx_bytes: Bytes,
+    chain_id: L2ChainId,
+) -> Result<L2Tx, Web3Error> {
+    let (tx_req, hash) = TransactionRequest::from_bytes(&tx_bytes.0, chain_id)
+        .map_err(Web3Error::SerializationError)?;
+    let mut l2_tx =
+        L2Tx::from_request(tx_req, MAX_TX_SIZE).map_err(Web3Error::SerializationError)?;
+
+    l2_tx.set_input(tx_bytes.0, hash);
+    if hash != l2_tx.hash() {
+        return Err(Web3Error::InternalError(anyhow::Error::msg(
+            "Invalid transaction data: computed hash does not match the provided hash.",
+        )));
+    }
+
+    Ok(l2_tx)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        config::{cache::CacheConfig, gas::DEFAULT_L2_GAS_PRICE},
+        config::{
+            accounts::{AccountsConfig, DevAccount},
+            cache::CacheConfig,
+            gas::DEFAULT_L2_GAS_PRICE,
+        },
+        constants::RICH_WALLETS,
         fork::ForkDetails,
         http_fork_source::HttpForkSource,
         node::{compute_hash, InMemoryNode, Snapshot},
@@ -1512,6 +1744,7 @@ mod tests {
         },
     };
     use maplit::hashmap;
+    use std::str::FromStr;
     use zksync_basic_types::{web3, Nonce};
     use zksync_types::{
         api::{BlockHashObject, BlockNumber, BlockNumberObject, TransactionReceipt},
@@ -2371,27 +2604,91 @@ mod tests {
         let inner = node.get_inner();
         let reader = inner.read().unwrap();
         for miniblock in initial_miniblock..current_miniblock {
-            let actual_cached_value = reader
-                .block_hashes
-                .get(&miniblock)
-                .map(|hash| {
-                    reader
-                        .previous_states
-                        .get(hash)
-                        .unwrap_or_else(|| panic!("state was not cached for block {}", miniblock))
-                })
-                .and_then(|state| state.get(&input_storage_key))
-                .copied();
+            let diffs = reader
+                .archived_state_at(Some(BlockIdVariant::BlockNumber(BlockNumber::Number(
+                    U64::from(miniblock),
+                ))))
+                .unwrap_or_else(|err| {
+                    panic!("state was not cached for block {}: {}", miniblock, err)
+                });
+            let actual_cached_value = HistoricalStorage::new(&reader.fork_storage, diffs)
+                .read_value_internal(&input_storage_key)
+                .expect("failed reading archived value");
 
             assert_eq!(
-                Some(input_storage_value),
-                actual_cached_value,
+                input_storage_value, actual_cached_value,
                 "unexpected cached state value for block {}",
                 miniblock
             );
         }
     }
 
+    #[tokio::test]
+    async fn test_get_balance_reads_archived_state_beyond_previous_states_limit() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        node.get_inner().write().unwrap().config.archive = true;
+
+        let address = H160::repeat_byte(0x1);
+        let balance_key = storage_key_for_standard_token_balance(
+            AccountTreeId::new(L2_BASE_TOKEN_ADDRESS),
+            &address,
+        );
+        node.get_inner()
+            .write()
+            .unwrap()
+            .fork_storage
+            .set_value(balance_key, u256_to_h256(U256::from(100)));
+        node.mine_blocks(Some(U64::from(1)), None)
+            .expect("failed mining block");
+
+        node.get_inner()
+            .write()
+            .unwrap()
+            .fork_storage
+            .set_value(balance_key, u256_to_h256(U256::from(200)));
+        node.mine_blocks(Some(U64::from(MAX_PREVIOUS_STATES as u64 + 2)), None)
+            .expect("failed mining blocks");
+
+        let historical_balance = node
+            .get_balance(
+                address,
+                Some(BlockIdVariant::BlockNumber(
+                    BlockNumber::Number(U64::zero()),
+                )),
+            )
+            .await
+            .expect("failed retrieving historical balance");
+        assert_eq!(U256::from(100), historical_balance);
+
+        let latest_balance = node
+            .get_balance(address, None)
+            .await
+            .expect("failed retrieving latest balance");
+        assert_eq!(U256::from(200), latest_balance);
+    }
+
+    #[tokio::test]
+    async fn test_get_balance_fails_for_evicted_state_without_archive() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        node.mine_blocks(Some(U64::from(MAX_PREVIOUS_STATES as u64 + 2)), None)
+            .expect("failed mining blocks");
+
+        let error = node
+            .get_balance(
+                H160::repeat_byte(0x1),
+                Some(BlockIdVariant::BlockNumber(
+                    BlockNumber::Number(U64::zero()),
+                )),
+            )
+            .await
+            .expect_err("expected the state to be evicted");
+        assert!(
+            error.message.contains("is not archived"),
+            "unexpected error: {}",
+            error.message
+        );
+    }
+
     #[tokio::test]
     async fn test_get_storage_fetches_zero_value_for_non_existent_key() {
         let node = InMemoryNode::<HttpForkSource>::default();
@@ -2456,6 +2753,59 @@ mod tests {
         assert_eq!(input_storage_value, actual_value);
     }
 
+    #[tokio::test]
+    async fn test_get_balance_uses_fork_to_get_value_for_block_before_fork() {
+        let mock_server = MockServer::run_with_config(ForkBlockConfig {
+            number: 10,
+            transaction_count: 0,
+            hash: H256::repeat_byte(0xab),
+        });
+        let input_address = H160::repeat_byte(0x1);
+        let balance_key = storage_key_for_standard_token_balance(
+            AccountTreeId::new(L2_BASE_TOKEN_ADDRESS),
+            &input_address,
+        );
+        mock_server.expect(
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "method": "eth_getStorageAt",
+                "params": [
+                    format!("{:#x}", L2_BASE_TOKEN_ADDRESS),
+                    format!("{:#x}", h256_to_u256(*balance_key.key())),
+                    { "blockNumber": "0x2" },
+                ],
+            }),
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "result": format!("{:#x}", u256_to_h256(U256::from(1337))),
+            }),
+        );
+
+        let node = InMemoryNode::<HttpForkSource>::new(
+            Some(
+                ForkDetails::from_network(&mock_server.url(), None, CacheConfig::None)
+                    .await
+                    .unwrap(),
+            ),
+            None,
+            Default::default(),
+            Default::default(),
+        );
+
+        let balance = node
+            .get_balance(
+                input_address,
+                Some(zksync_types::api::BlockIdVariant::BlockNumber(
+                    BlockNumber::Number(U64::from(2)),
+                )),
+            )
+            .await
+            .expect("failed retrieving balance");
+        assert_eq!(U256::from(1337), balance);
+    }
+
     #[tokio::test]
     async fn test_get_storage_uses_archived_storage_to_get_value_for_missing_key() {
         let input_address = H160::repeat_byte(0x1);
@@ -2480,7 +2830,8 @@ mod tests {
                     historical_block.hash,
                     hashmap! {
                         input_storage_key => input_storage_value,
-                    },
+                    }
+                    .into(),
                 );
                 writer
                     .blocks
@@ -2840,6 +3191,225 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_accounts_returns_the_dev_accounts_in_order_after_reset() {
+        let config = AccountsConfig {
+            mnemonic: Some(
+                "test test test test test test test test test test test junk".to_string(),
+            ),
+            accounts: 3,
+            balance: 100,
+            ..Default::default()
+        };
+        let dev_accounts = config.dev_accounts().expect("failed deriving dev accounts");
+        let node = InMemoryNode::<HttpForkSource>::default()
+            .with_dev_accounts(dev_accounts.clone(), config.balance_wei());
+        node.fund_dev_accounts();
+
+        node.reset(None).expect("failed resetting node");
+
+        let accounts = node.accounts().await.expect("failed fetching accounts");
+        let expected_accounts: Vec<H160> =
+            dev_accounts.iter().map(|account| account.address).collect();
+        assert_eq!(expected_accounts, accounts);
+        let balance = node
+            .get_balance(accounts[0], None)
+            .await
+            .expect("failed fetching balance");
+        assert_eq!(U256::from(100) * U256::exp10(18), balance);
+    }
+
+    #[tokio::test]
+    async fn test_sign_and_personal_sign_sign_as_the_dev_account() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let address = H160::from_str(RICH_WALLETS[0].0).unwrap();
+        let message = Bytes(b"hello".to_vec());
+
+        let signature = EthTestNodeNamespaceT::sign(&node, address, message.clone())
+            .await
+            .expect("failed signing message");
+        let personal_signature = node
+            .personal_sign(message.clone(), address)
+            .await
+            .expect("failed signing message");
+
+        assert_eq!(signature, personal_signature);
+        let signer = PackedEthSignature::deserialize_packed(&signature.0)
+            .unwrap()
+            .signature_recover_signer(&PackedEthSignature::message_to_signed_bytes(&message.0))
+            .unwrap();
+        assert_eq!(address, signer);
+    }
+
+    #[tokio::test]
+    async fn test_sign_fails_for_unknown_account() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+
+        let result =
+            EthTestNodeNamespaceT::sign(&node, H160::repeat_byte(0x1), Bytes(b"hello".to_vec()))
+                .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sign_typed_data_matches_eip712_example() {
+        // the example of the EIP-712 specification, signed by the private key `keccak256("cow")`
+        let address = H160::from_str("0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826").unwrap();
+        let private_key =
+            H256::from_str("0xc85ef7d79691fe79573b1a7064c19c1a9819ebdbd1faaab1a8ec92344438aaf4")
+                .unwrap();
+        let node = InMemoryNode::<HttpForkSource>::default().with_dev_accounts(
+            vec![DevAccount {
+                address,
+                private_key,
+                mnemonic: None,
+            }],
+            U256::zero(),
+        );
+        let typed_data: TypedData = serde_json::from_value(serde_json::json!({
+            "types": {
+                "EIP712Domain": [
+                    { "name": "name", "type": "string" },
+                    { "name": "version", "type": "string" },
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" }
+                ],
+                "Person": [
+                    { "name": "name", "type": "string" },
+                    { "name": "wallet", "type": "address" }
+                ],
+                "Mail": [
+                    { "name": "from", "type": "Person" },
+                    { "name": "to", "type": "Person" },
+                    { "name": "contents", "type": "string" }
+                ]
+            },
+            "primaryType": "Mail",
+            "domain": {
+                "name": "Ether Mail",
+                "version": "1",
+                "chainId": 1,
+                "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+            },
+            "message": {
+                "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+                "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+                "contents": "Hello, Bob!"
+            }
+        }))
+        .unwrap();
+
+        let signature = node
+            .sign_typed_data(address, typed_data)
+            .await
+            .expect("failed signing typed data");
+
+        let expected_signature = Bytes(
+            hex::decode(concat!(
+                "4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d",
+                "07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b91562",
+                "1c"
+            ))
+            .unwrap(),
+        );
+        assert_eq!(expected_signature, signature);
+    }
+
+    fn transfer_request(
+        from: H160,
+        custom_data: Option<serde_json::Value>,
+    ) -> SendTransactionRequest {
+        let mut request = serde_json::json!({
+            "from": from,
+            "to": H160::repeat_byte(0x1),
+            "value": "0x1",
+        });
+        if let Some(custom_data) = custom_data {
+            request["customData"] = custom_data;
+        }
+        serde_json::from_value(request).expect("invalid transaction request")
+    }
+
+    #[tokio::test]
+    async fn test_sign_transaction_is_signed_by_the_dev_account() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let from = H160::from_str(RICH_WALLETS[0].0).unwrap();
+
+        let raw_tx = node
+            .sign_transaction(transfer_request(from, None))
+            .await
+            .expect("failed signing transaction");
+
+        let chain_id = node.get_inner().read().unwrap().fork_storage.chain_id;
+        let l2_tx = decode_raw_transaction(raw_tx, chain_id).expect("invalid signed transaction");
+        assert_eq!(from, l2_tx.initiator_account());
+        assert_eq!(Nonce(0), l2_tx.common_data.nonce);
+    }
+
+    #[tokio::test]
+    async fn test_send_transaction_from_dev_account_is_signed() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        node.fund_dev_accounts();
+        let from = H160::from_str(RICH_WALLETS[0].0).unwrap();
+
+        let first_hash = node
+            .send_transaction(transfer_request(from, None))
+            .await
+            .expect("failed sending transaction");
+        let second_hash = node
+            .send_transaction(transfer_request(from, None))
+            .await
+            .expect("failed sending transaction");
+
+        let tx = node
+            .get_transaction_by_hash(second_hash)
+            .await
+            .expect("failed fetching transaction")
+            .expect("missing transaction");
+        assert_ne!(first_hash, second_hash);
+        assert_eq!(from, tx.from.unwrap());
+        assert_eq!(U256::from(1), tx.nonce);
+        assert!(tx.r.is_some_and(|r| !r.is_zero()));
+        assert!(tx.s.is_some_and(|s| !s.is_zero()));
+    }
+
+    #[tokio::test]
+    async fn test_send_transaction_with_custom_data_is_an_eip712_transaction() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        node.fund_dev_accounts();
+        let from = H160::from_str(RICH_WALLETS[0].0).unwrap();
+
+        let hash = node
+            .send_transaction(transfer_request(
+                from,
+                Some(serde_json::json!({ "gasPerPubdata": "0xc350" })),
+            ))
+            .await
+            .expect("failed sending transaction");
+
+        let tx = node
+            .get_transaction_by_hash(hash)
+            .await
+            .expect("failed fetching transaction")
+            .expect("missing transaction");
+        assert_eq!(
+            Some(U64::from(zksync_types::EIP_712_TX_TYPE)),
+            tx.transaction_type
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_transaction_fails_from_unknown_account() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+
+        let result = node
+            .send_transaction(transfer_request(H160::repeat_byte(0x2), None))
+            .await;
+
+        assert!(result.is_err());
+    }
+
     #[tokio::test]
     async fn test_snapshot() {
         let node = InMemoryNode::<HttpForkSource>::default();
@@ -2891,6 +3461,7 @@ mod tests {
             rich_accounts: inner.rich_accounts.clone(),
             previous_states: inner.previous_states.clone(),
             raw_storage: storage.raw_storage.clone(),
+            journal: storage.journal.clone(),
             value_read_cache: storage.value_read_cache.clone(),
             factory_dep_cache: storage.factory_dep_cache.clone(),
         };
@@ -2936,6 +3507,7 @@ mod tests {
             actual_snapshot.previous_states
         );
         assert_eq!(expected_snapshot.raw_storage, actual_snapshot.raw_storage);
+        assert_eq!(expected_snapshot.journal, actual_snapshot.journal);
         assert_eq!(
             expected_snapshot.value_read_cache,
             actual_snapshot.value_read_cache
@@ -2998,6 +3570,7 @@ mod tests {
                 rich_accounts: inner.rich_accounts.clone(),
                 previous_states: inner.previous_states.clone(),
                 raw_storage: storage.raw_storage.clone(),
+                journal: storage.journal.clone(),
                 value_read_cache: storage.value_read_cache.clone(),
                 factory_dep_cache: storage.factory_dep_cache.clone(),
             }
@@ -3067,6 +3640,7 @@ mod tests {
         assert_eq!(expected_snapshot.rich_accounts, inner.rich_accounts);
         assert_eq!(expected_snapshot.previous_states, inner.previous_states);
         assert_eq!(expected_snapshot.raw_storage, storage.raw_storage);
+        assert_eq!(expected_snapshot.journal, storage.journal);
         assert_eq!(expected_snapshot.value_read_cache, storage.value_read_cache);
         assert_eq!(
             expected_snapshot.factory_dep_cache,
@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use jsonrpc_core::Error;
+use jsonrpc_pubsub::{typed::Subscriber, Session, SubscriptionId};
+use zksync_web3_decl::types::{PubSubFilter, PubSubResult};
+
+use crate::{
+    fork::ForkSource,
+    namespaces::{EthPubSubNamespaceT, Result},
+    node::InMemoryNode,
+    subscriptions::SubscriptionKind,
+    utils::into_jsrpc_error_message,
+};
+
+impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EthPubSubNamespaceT
+    for InMemoryNode<S>
+{
+    type Metadata = Option<Arc<Session>>;
+
+    fn subscribe(
+        &self,
+        _meta: Self::Metadata,
+        subscriber: Subscriber<PubSubResult>,
+        sub_type: String,
+        params: Option<PubSubFilter>,
+    ) {
+        let kind = match SubscriptionKind::new(&sub_type, params) {
+            Ok(kind) => kind,
+            Err(err) => {
+                let _ = subscriber.reject(Error::invalid_params(err));
+                return;
+            }
+        };
+        let subscriptions = match self.get_inner().read() {
+            Ok(reader) => reader.subscriptions.clone(),
+            Err(_) => {
+                let _ = subscriber.reject(into_jsrpc_error_message(
+                    "Failed to acquire read lock for subscription".to_string(),
+                ));
+                return;
+            }
+        };
+
+        if let Err(err) = subscriptions.add(kind, subscriber) {
+            tracing::error!("failed creating subscription: {}", err);
+        }
+    }
+
+    fn unsubscribe(&self, _meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
+        self.get_inner()
+            .read()
+            .map(|reader| reader.subscriptions.remove(&id))
+            .map_err(|_| {
+                into_jsrpc_error_message("Failed to acquire read lock for subscription".to_string())
+            })
+    }
+}
@@ -1,10 +1,11 @@
+use futures::FutureExt;
 use zksync_basic_types::{Address, U256, U64};
 use zksync_web3_decl::error::Web3Error;
 
 use crate::{
     fork::ForkSource,
-    namespaces::{EvmNamespaceT, RpcResult},
-    node::InMemoryNode,
+    namespaces::{EvmNamespaceT, MineOptions, RpcResult},
+    node::{eth::decode_raw_transaction, InMemoryNode},
     utils::{into_jsrpc_error, IntoBoxedFuture},
 };
 
@@ -29,8 +30,36 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EvmNamespa
             .into_boxed_future()
     }
 
-    fn evm_mine(&self) -> RpcResult<String> {
-        self.mine_block()
+    fn evm_mine(&self, options: Option<MineOptions>) -> RpcResult<String> {
+        let (timestamp, blocks, transactions) = match options {
+            None => (None, None, vec![]),
+            Some(MineOptions::Timestamp(timestamp)) => (Some(timestamp), None, vec![]),
+            Some(MineOptions::Options {
+                timestamp,
+                blocks,
+                transactions,
+            }) => (timestamp, blocks, transactions),
+        };
+
+        let chain_id = match self.get_inner().read() {
+            Ok(reader) => reader.fork_storage.chain_id,
+            Err(_) => {
+                return futures::future::err(into_jsrpc_error(Web3Error::InternalError(
+                    anyhow::Error::msg("Failed to acquire read lock for chain ID retrieval"),
+                )))
+                .boxed()
+            }
+        };
+        let txs = match transactions
+            .into_iter()
+            .map(|tx_bytes| decode_raw_transaction(tx_bytes, chain_id))
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(txs) => txs,
+            Err(e) => return futures::future::err(into_jsrpc_error(e)).boxed(),
+        };
+
+        self.mine_with_options(timestamp, blocks, txs)
             .map_err(|err| {
                 tracing::error!("failed mining block: {:?}", err);
                 into_jsrpc_error(Web3Error::InternalError(err))
@@ -73,4 +102,31 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> EvmNamespa
             })
             .into_boxed_future()
     }
+
+    fn set_automine(&self, enabled: bool) -> RpcResult<bool> {
+        self.set_automine(enabled)
+            .map_err(|err| {
+                tracing::error!("failed setting automine: {:?}", err);
+                into_jsrpc_error(Web3Error::InternalError(err))
+            })
+            .into_boxed_future()
+    }
+
+    fn set_interval_mining(&self, interval_ms: u64) -> RpcResult<bool> {
+        self.set_interval_mining(interval_ms)
+            .map_err(|err| {
+                tracing::error!("failed setting interval mining: {:?}", err);
+                into_jsrpc_error(Web3Error::InternalError(err))
+            })
+            .into_boxed_future()
+    }
+
+    fn set_block_gas_limit(&self, gas_limit: U64) -> RpcResult<bool> {
+        self.set_block_gas_limit(gas_limit)
+            .map_err(|err| {
+                tracing::error!("failed setting block gas limit: {:?}", err);
+                into_jsrpc_error(Web3Error::InternalError(err))
+            })
+            .into_boxed_future()
+    }
 }
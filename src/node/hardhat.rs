@@ -1,4 +1,4 @@
-use zksync_basic_types::{Address, U256, U64};
+use zksync_basic_types::{Address, H256, U256, U64};
 use zksync_web3_decl::error::Web3Error;
 
 use crate::{
@@ -82,4 +82,23 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> HardhatNam
             })
             .into_boxed_future()
     }
+
+    fn get_automine(&self) -> RpcResult<bool> {
+        self.get_automine()
+            .map_err(|err| {
+                tracing::error!("failed getting automine: {:?}", err);
+                into_jsrpc_error(Web3Error::InternalError(err))
+            })
+            .into_boxed_future()
+    }
+
+    fn drop_transaction(&self, hash: H256) -> RpcResult<bool> {
+        self.drop_transaction(hash)
+            .map(|dropped| dropped.is_some())
+            .map_err(|err| {
+                tracing::error!("failed dropping transaction: {:?}", err);
+                into_jsrpc_error(Web3Error::InternalError(err))
+            })
+            .into_boxed_future()
+    }
 }
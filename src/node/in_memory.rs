@@ -2,34 +2,41 @@
 use crate::{
     bootloader_debug::{BootloaderDebug, BootloaderDebugTracer},
     config::{
+        accounts::{AccountsConfig, DevAccount},
         cache::CacheConfig,
         gas::{self, GasConfig},
         node::{InMemoryNodeConfig, ShowCalls, ShowGasDetails, ShowStorageLogs, ShowVMDetails},
     },
     console_log::ConsoleLogHandler,
-    constants::{LEGACY_RICH_WALLETS, RICH_WALLETS},
     deps::{storage_view::StorageView, InMemoryStorage},
     filters::EthFilters,
-    fork::{block_on, ForkDetails, ForkSource, ForkStorage},
-    formatter,
-    node::{fee_model::TestNodeFeeInputProvider, storage_logs::print_storage_logs_details},
+    fork::{block_on, ForkDetails, ForkSource, ForkStorage, HistoricalState, HistoricalStorage},
+    formatter, metrics,
+    node::{
+        batch::BatchUsage, fee_model::TestNodeFeeInputProvider, pool::TxPool,
+        storage_logs::print_storage_logs_details,
+    },
     observability::Observability,
+    subscriptions::EthSubscriptions,
     system_contracts::{self, SystemContracts},
-    utils::{bytecode_to_factory_dep, create_debug_output, into_jsrpc_error, to_human_size},
+    utils::{
+        bytecode_to_factory_dep, create_debug_output, into_jsrpc_error, to_human_size,
+        to_real_block_number,
+    },
 };
 use colored::Colorize;
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
 use once_cell::sync::OnceCell;
 use std::{
     collections::{HashMap, HashSet},
-    str::FromStr,
+    path::PathBuf,
     sync::{Arc, RwLock},
 };
 
 use multivm::{
     interface::{
         ExecutionResult, L1BatchEnv, L2BlockEnv, SystemEnv, TxExecutionMode, VmExecutionMode,
-        VmExecutionResultAndLogs, VmInterface,
+        VmExecutionResultAndLogs, VmInterface, VmInterfaceHistoryEnabled,
     },
     vm_latest::{constants::BATCH_COMPUTATIONAL_GAS_LIMIT, L2Block},
     VmVersion,
@@ -40,23 +47,21 @@ use multivm::{
         adjust_pubdata_price_for_tx, derive_base_fee_and_gas_per_pubdata, derive_overhead,
         get_max_gas_per_pubdata_byte,
     },
-    vm_latest::HistoryDisabled,
     vm_latest::{
-        constants::{BATCH_GAS_LIMIT, MAX_VM_PUBDATA_PER_BATCH},
-        utils::l2_blocks::load_last_l2_block,
-        ToTracerPointer, TracerPointer, Vm,
+        constants::BATCH_GAS_LIMIT, utils::l2_blocks::load_last_l2_block, ToTracerPointer,
+        TracerPointer, Vm,
     },
+    vm_latest::{HistoryDisabled, HistoryEnabled},
 };
-use std::convert::TryInto;
 use zksync_basic_types::{
-    web3::keccak256, web3::Bytes, AccountTreeId, Address, L1BatchNumber, L2BlockNumber, H160, H256,
-    U256, U64,
+    web3::keccak256, web3::Bytes, AccountTreeId, Address, L1BatchNumber, L2BlockNumber, Nonce,
+    H160, H256, U256, U64,
 };
 use zksync_contracts::BaseSystemContracts;
 use zksync_node_fee_model::BatchFeeModelInputProvider;
 use zksync_state::{ReadStorage, StoragePtr, WriteStorage};
 use zksync_types::{
-    api::{Block, DebugCall, Log, TransactionReceipt, TransactionVariant},
+    api::{Block, BlockIdVariant, DebugCall, Log, TransactionReceipt, TransactionVariant},
     block::{unpack_block_info, L2BlockHasher},
     fee::Fee,
     fee_model::{BatchFeeInput, PubdataIndependentBatchFeeModelInput},
@@ -65,7 +70,7 @@ use zksync_types::{
     l2::TransactionType,
     utils::{decompose_full_nonce, nonces_to_full_nonce, storage_key_for_eth_balance},
     vm_trace::Call,
-    PackedEthSignature, StorageKey, StorageLogQueryType, StorageValue, Transaction,
+    K256PrivateKey, PackedEthSignature, StorageKey, StorageLogQueryType, StorageValue, Transaction,
     ACCOUNT_CODE_STORAGE_ADDRESS, MAX_L2_TX_GAS_LIMIT, SYSTEM_CONTEXT_ADDRESS,
     SYSTEM_CONTEXT_BLOCK_INFO_POSITION,
 };
@@ -80,7 +85,7 @@ pub const NON_FORK_FIRST_BLOCK_TIMESTAMP: u64 = 1_000;
 pub const TEST_NODE_NETWORK_ID: u32 = 260;
 /// Acceptable gas overestimation limit.
 pub const ESTIMATE_GAS_ACCEPTABLE_OVERESTIMATION: u64 = 1_000;
-/// The maximum number of previous blocks to store the state for.
+/// The maximum number of previous blocks to store the state for, unless running in archive mode.
 pub const MAX_PREVIOUS_STATES: u16 = 128;
 /// The zks protocol version.
 pub const PROTOCOL_VERSION: &str = "zks/1";
@@ -95,6 +100,7 @@ pub fn create_empty_block<TX>(
     timestamp: u64,
     batch: u32,
     parent_block_hash: Option<H256>,
+    gas_limit: u64,
 ) -> Block<TX> {
     let hash = compute_hash(block_number, H256::zero());
     let parent_hash = parent_block_hash.unwrap_or(if block_number == 0 {
@@ -110,7 +116,7 @@ pub fn create_empty_block<TX>(
         l1_batch_number: Some(U64::from(batch)),
         transactions: vec![],
         gas_used: U256::from(0),
-        gas_limit: U256::from(BATCH_GAS_LIMIT),
+        gas_limit: U256::from(gas_limit),
         ..Default::default()
     }
 }
@@ -166,13 +172,15 @@ pub struct InMemoryNodeInner<S> {
     /// The fee input provider.
     pub fee_input_provider: TestNodeFeeInputProvider,
     // Map from transaction to details about the exeuction
-    pub tx_results: HashMap<H256, TransactionResult>,
+    pub tx_results: im::HashMap<H256, TransactionResult>,
     // Map from block hash to information about the block.
-    pub blocks: HashMap<H256, Block<TransactionVariant>>,
+    pub blocks: im::HashMap<H256, Block<TransactionVariant>>,
     // Map from block number to a block hash.
-    pub block_hashes: HashMap<u64, H256>,
+    pub block_hashes: im::HashMap<u64, H256>,
     // Map from filter_id to the eth filter
     pub filters: EthFilters,
+    /// The `eth_subscribe` subscriptions, shared with the snapshots and across resets.
+    pub subscriptions: EthSubscriptions,
     // Underlying storage
     pub fork_storage: ForkStorage<S>,
     // Configuration.
@@ -180,11 +188,18 @@ pub struct InMemoryNodeInner<S> {
     pub console_log_handler: ConsoleLogHandler,
     pub system_contracts: SystemContracts,
     pub impersonated_accounts: HashSet<Address>,
-    pub rich_accounts: HashSet<H160>,
-    /// Keeps track of historical states indexed via block hash. Limited to [MAX_PREVIOUS_STATES].
-    pub previous_states: IndexMap<H256, HashMap<StorageKey, StorageValue>>,
+    /// Accounts funded via [InMemoryNode::set_rich_account], in the order they were funded.
+    pub rich_accounts: IndexSet<H160>,
+    /// Keeps track of historical states indexed via block hash, as the previous values of the keys
+    /// written after each block. Limited to [MAX_PREVIOUS_STATES], unless running in archive mode.
+    pub previous_states: IndexMap<H256, im::HashMap<StorageKey, StorageValue>>,
     /// An optional handle to the observability stack
     pub observability: Option<Observability>,
+    /// Transactions that were submitted, but not yet sealed into a block.
+    pub pool: TxPool,
+    /// If true, every submitted transaction is immediately sealed into a new block.
+    /// Otherwise transactions wait in the [TxPool] until a block is mined explicitly.
+    pub automine: bool,
 }
 
 type L2TxResult = (
@@ -205,9 +220,9 @@ impl<S: std::fmt::Debug + ForkSource> InMemoryNodeInner<S> {
         gas_overrides: Option<GasConfig>,
     ) -> Self {
         if let Some(f) = &fork {
-            let mut block_hashes = HashMap::<u64, H256>::new();
+            let mut block_hashes = im::HashMap::<u64, H256>::new();
             block_hashes.insert(f.l2_block.number.as_u64(), f.l2_block.hash);
-            let mut blocks = HashMap::<H256, Block<TransactionVariant>>::new();
+            let mut blocks = im::HashMap::<H256, Block<TransactionVariant>>::new();
             blocks.insert(f.l2_block.hash, f.l2_block.clone());
 
             let fee_input_provider = if let Some(params) = f.fee_params {
@@ -235,23 +250,32 @@ impl<S: std::fmt::Debug + ForkSource> InMemoryNodeInner<S> {
                 blocks,
                 block_hashes,
                 filters: Default::default(),
+                subscriptions: Default::default(),
                 fork_storage: ForkStorage::new(fork, &config.system_contracts_options),
                 config,
                 console_log_handler: ConsoleLogHandler::default(),
                 system_contracts: SystemContracts::from_options(&config.system_contracts_options),
                 impersonated_accounts: Default::default(),
-                rich_accounts: HashSet::new(),
+                rich_accounts: IndexSet::new(),
                 previous_states: Default::default(),
                 observability,
+                pool: Default::default(),
+                automine: config.block_time.is_none(),
             }
         } else {
-            let mut block_hashes = HashMap::<u64, H256>::new();
+            let mut block_hashes = im::HashMap::<u64, H256>::new();
             let block_hash = compute_hash(0, H256::zero());
             block_hashes.insert(0, block_hash);
-            let mut blocks = HashMap::<H256, Block<TransactionVariant>>::new();
+            let mut blocks = im::HashMap::<H256, Block<TransactionVariant>>::new();
             blocks.insert(
                 block_hash,
-                create_empty_block(0, NON_FORK_FIRST_BLOCK_TIMESTAMP, 0, None),
+                create_empty_block(
+                    0,
+                    NON_FORK_FIRST_BLOCK_TIMESTAMP,
+                    0,
+                    None,
+                    config.block_gas_limit,
+                ),
             );
 
             let fee_input_provider =
@@ -266,14 +290,17 @@ impl<S: std::fmt::Debug + ForkSource> InMemoryNodeInner<S> {
                 blocks,
                 block_hashes,
                 filters: Default::default(),
+                subscriptions: Default::default(),
                 fork_storage: ForkStorage::new(fork, &config.system_contracts_options),
                 config,
                 console_log_handler: ConsoleLogHandler::default(),
                 system_contracts: SystemContracts::from_options(&config.system_contracts_options),
                 impersonated_accounts: Default::default(),
-                rich_accounts: HashSet::new(),
+                rich_accounts: IndexSet::new(),
                 previous_states: Default::default(),
                 observability,
+                pool: Default::default(),
+                automine: config.block_time.is_none(),
             }
         }
     }
@@ -512,7 +539,7 @@ impl<S: std::fmt::Debug + ForkSource> InMemoryNodeInner<S> {
                 &self.fork_storage,
             );
 
-            if result.statistics.pubdata_published > MAX_VM_PUBDATA_PER_BATCH.try_into().unwrap() {
+            if result.statistics.pubdata_published as u64 > self.config.seal_criteria.max_pubdata {
                 return Err(into_jsrpc_error(Web3Error::SubmitTransactionError(
                     "exceeds limit for published pubdata".into(),
                     Default::default(),
@@ -753,9 +780,88 @@ impl<S: std::fmt::Debug + ForkSource> InMemoryNodeInner<S> {
         self.impersonated_accounts.remove(&address)
     }
 
-    /// Archives the current state for later queries.
+    /// Notifies the filters and the subscriptions of a transaction added to the pool.
+    pub fn notify_new_pending_transaction(&mut self, hash: H256) {
+        self.filters.notify_new_pending_transaction(hash);
+        self.subscriptions.notify_new_pending_transaction(hash);
+    }
+
+    /// Notifies the filters and the subscriptions of blocks committed by [InMemoryNode::seal_block], and of the
+    /// logs emitted in them.
+    ///
+    /// The sealed transactions may have filled the nonce gaps of queued transactions, so the queued transactions
+    /// of their senders are promoted to the pending pool first.
+    fn notify_sealed_blocks(&mut self, sealed: Vec<SealedBlock>) {
+        self.promote_queued_txs(&sealed);
+        for SealedBlock { block, logs } in sealed {
+            for log in &logs {
+                self.filters.notify_new_log(log, block.number);
+                self.subscriptions.notify_log(log);
+            }
+            self.filters.notify_new_block(block.hash);
+            self.subscriptions.notify_new_block(&block);
+        }
+    }
+
+    /// Promotes the queued transactions of the senders of the transactions in the given blocks.
+    fn promote_queued_txs(&mut self, sealed: &[SealedBlock]) {
+        let senders = sealed
+            .iter()
+            .flat_map(|SealedBlock { block, .. }| &block.transactions)
+            .filter_map(|tx| match tx {
+                TransactionVariant::Full(tx) => tx.from,
+                TransactionVariant::Hash(_) => None,
+            })
+            .collect::<HashSet<_>>();
+        for sender in senders {
+            let account_nonce = match self.account_nonce(sender) {
+                Ok(account_nonce) => account_nonce,
+                Err(e) => {
+                    tracing::error!(
+                        "Failed promoting queued transactions of {:?}: {}",
+                        sender,
+                        e
+                    );
+                    continue;
+                }
+            };
+            for promoted_hash in self.pool.promote(sender, account_nonce) {
+                tracing::info!(
+                    "Queued transaction {:?} promoted to the pending pool",
+                    promoted_hash
+                );
+            }
+        }
+    }
+
+    /// Returns the current nonce of the given account, i.e. the nonce of its next transaction.
+    pub fn account_nonce(&self, address: Address) -> Result<Nonce, String> {
+        let full_nonce = self
+            .fork_storage
+            .read_value_internal(&get_nonce_key(&address))
+            .map_err(|err| format!("failed reading nonce: {:?}", err))?;
+        let (account_nonce, _) = decompose_full_nonce(h256_to_u256(full_nonce));
+        Ok(Nonce(account_nonce.as_u32()))
+    }
+
+    /// Returns the nonce of the next transaction of the given account, following its pending ones.
+    pub fn next_nonce(&self, address: Address) -> Result<Nonce, String> {
+        Ok(self.pool.next_nonce(address, self.account_nonce(address)?))
+    }
+
+    /// Archives the state of the current block, so it can still be read after the block is modified.
+    ///
+    /// Only the previous values of the keys written since the last archived block are kept, so the
+    /// cost of archiving scales with the number of writes rather than the size of the storage.
     pub fn archive_state(&mut self) -> Result<(), String> {
-        if self.previous_states.len() > MAX_PREVIOUS_STATES as usize {
+        let journal = self.fork_storage.take_journal();
+        if let Some((_, diff)) = self.previous_states.last_mut() {
+            for (key, value) in journal {
+                diff.entry(key).or_insert(value);
+            }
+        }
+
+        if !self.config.archive && self.previous_states.len() > MAX_PREVIOUS_STATES as usize {
             if let Some(entry) = self.previous_states.shift_remove_index(0) {
                 tracing::debug!("removing archived state for previous block {:#x}", entry.0);
             }
@@ -765,16 +871,158 @@ impl<S: std::fmt::Debug + ForkSource> InMemoryNodeInner<S> {
             self.current_miniblock_hash,
             self.current_miniblock
         );
-        self.previous_states.insert(
-            self.current_miniblock_hash,
-            self.fork_storage
+        self.previous_states
+            .entry(self.current_miniblock_hash)
+            .or_default();
+
+        Ok(())
+    }
+
+    /// Returns the state needed to read the storage as of the given block, or `None` if it refers to the
+    /// latest block.
+    ///
+    /// The state of a local block is given by the archived diffs of that block and all the blocks after it,
+    /// while the state of a block before the fork is read from the fork source.
+    pub fn archived_state_at(
+        &self,
+        block: Option<BlockIdVariant>,
+    ) -> Result<Option<HistoricalState<'_>>, String> {
+        let block_number = match block {
+            None => return Ok(None),
+            Some(BlockIdVariant::BlockNumber(number)) => {
+                to_real_block_number(number, U64::from(self.current_miniblock))
+            }
+            Some(BlockIdVariant::BlockNumberObject(o)) => {
+                to_real_block_number(o.block_number, U64::from(self.current_miniblock))
+            }
+            Some(BlockIdVariant::BlockHashObject(o)) => self
+                .blocks
+                .get(&o.block_hash)
+                .map(|block| block.number)
+                .ok_or_else(|| format!("block {:#x} not found", o.block_hash))?,
+        }
+        .as_u64();
+
+        if block_number == self.current_miniblock {
+            return Ok(None);
+        }
+        let Some(block_hash) = self.block_hashes.get(&block_number) else {
+            let forked_at = self
+                .fork_storage
                 .inner
                 .read()
-                .map_err(|err| err.to_string())?
-                .raw_storage
-                .state
-                .clone(),
-        );
+                .map_err(|err| format!("failed acquiring read lock on fork storage: {:?}", err))?
+                .fork
+                .as_ref()
+                .map(|fork| fork.l2_miniblock);
+            return match forked_at {
+                Some(forked_at) if block_number <= forked_at => {
+                    Ok(Some(HistoricalState::Fork(block_number)))
+                }
+                _ => Err(format!(
+                    "state for block #{} is not available",
+                    block_number
+                )),
+            };
+        };
+        let index = self.previous_states.get_index_of(block_hash).ok_or_else(|| {
+            format!(
+                "state for block #{} is not archived, run the node with `--archive` to keep the state of all blocks",
+                block_number
+            )
+        })?;
+
+        Ok(Some(HistoricalState::Archived(
+            self.previous_states.values().skip(index).collect(),
+        )))
+    }
+
+    /// Rolls the chain back to the given local block, discarding all the blocks after it along with their
+    /// transactions and state changes. Log filters are notified of the discarded logs, with `removed` set.
+    pub fn rollback_to(&mut self, block_number: u64) -> Result<(), String> {
+        if block_number >= self.current_miniblock {
+            return Err(format!(
+                "block #{} is not before the current block #{}",
+                block_number, self.current_miniblock
+            ));
+        }
+        let block_hash = *self
+            .block_hashes
+            .get(&block_number)
+            .ok_or_else(|| format!("block #{} is not available", block_number))?;
+        let index = self.previous_states.get_index_of(&block_hash).ok_or_else(|| {
+            format!(
+                "state for block #{} is not archived, run the node with `--archive` to keep the state of all blocks",
+                block_number
+            )
+        })?;
+
+        // the first archived value of each key written since the block is its value as of the block
+        let mut restored = HashMap::new();
+        for diff in self.previous_states.values().skip(index) {
+            for (key, value) in diff {
+                restored.entry(*key).or_insert(*value);
+            }
+        }
+        for (key, value) in self.fork_storage.take_journal() {
+            restored.entry(key).or_insert(value);
+        }
+        {
+            let mut storage = self
+                .fork_storage
+                .inner
+                .write()
+                .map_err(|err| format!("failed acquiring write lock on storage: {:?}", err))?;
+            for (key, value) in restored {
+                // archived zero values are mostly keys that were not written yet
+                if value.is_zero() {
+                    storage.raw_storage.state.remove(&key);
+                } else {
+                    storage.raw_storage.state.insert(key, value);
+                }
+            }
+        }
+        self.previous_states.truncate(index);
+
+        let mut removed_txs = self
+            .tx_results
+            .iter()
+            .filter(|(_, result)| result.info.miniblock_number > block_number)
+            .map(|(hash, result)| {
+                (
+                    result.info.miniblock_number,
+                    result.receipt.transaction_index,
+                    *hash,
+                )
+            })
+            .collect::<Vec<_>>();
+        removed_txs.sort();
+        for (miniblock_number, _, tx_hash) in removed_txs.into_iter().rev() {
+            if let Some(result) = self.tx_results.remove(&tx_hash) {
+                // filters are matched the same way as when the logs were added
+                for log in result.receipt.logs.iter().rev() {
+                    self.filters
+                        .notify_removed_log(log, U64::from(miniblock_number));
+                    self.subscriptions.notify_removed_log(log);
+                }
+            }
+        }
+        for number in block_number + 1..=self.current_miniblock {
+            if let Some(hash) = self.block_hashes.remove(&number) {
+                self.blocks.remove(&hash);
+            }
+        }
+
+        let block = self
+            .blocks
+            .get(&block_hash)
+            .ok_or_else(|| format!("block {:#x} not found", block_hash))?;
+        if let Some(batch) = block.l1_batch_number {
+            self.current_batch = batch.as_u32();
+        }
+        self.current_timestamp = block.timestamp.as_u64();
+        self.current_miniblock = block_number;
+        self.current_miniblock_hash = block_hash;
 
         Ok(())
     }
@@ -801,6 +1049,7 @@ impl<S: std::fmt::Debug + ForkSource> InMemoryNodeInner<S> {
             rich_accounts: self.rich_accounts.clone(),
             previous_states: self.previous_states.clone(),
             raw_storage: storage.raw_storage.clone(),
+            journal: storage.journal.clone(),
             value_read_cache: storage.value_read_cache.clone(),
             factory_dep_cache: storage.factory_dep_cache.clone(),
         })
@@ -827,6 +1076,7 @@ impl<S: std::fmt::Debug + ForkSource> InMemoryNodeInner<S> {
         self.rich_accounts = snapshot.rich_accounts;
         self.previous_states = snapshot.previous_states;
         storage.raw_storage = snapshot.raw_storage;
+        storage.journal = snapshot.journal;
         storage.value_read_cache = snapshot.value_read_cache;
         storage.factory_dep_cache = snapshot.factory_dep_cache;
 
@@ -836,6 +1086,9 @@ impl<S: std::fmt::Debug + ForkSource> InMemoryNodeInner<S> {
 
 /// Creates a restorable snapshot for the [InMemoryNodeInner]. The snapshot contains all the necessary
 /// data required to restore the [InMemoryNodeInner] state to a previous point in time.
+///
+/// The blocks, transactions and storage are kept in persistent maps, so a snapshot shares the unchanged
+/// entries with the live state, and taking or restoring it doesn't depend on the size of the state.
 #[derive(Debug, Clone, Default)]
 pub struct Snapshot {
     pub(crate) current_timestamp: u64,
@@ -845,16 +1098,17 @@ pub struct Snapshot {
     // Currently, the fee is static and the fee input provider is immutable during the test node life cycle,
     // but in the future, it may contain some mutable state.
     pub(crate) fee_input_provider: TestNodeFeeInputProvider,
-    pub(crate) tx_results: HashMap<H256, TransactionResult>,
-    pub(crate) blocks: HashMap<H256, Block<TransactionVariant>>,
-    pub(crate) block_hashes: HashMap<u64, H256>,
+    pub(crate) tx_results: im::HashMap<H256, TransactionResult>,
+    pub(crate) blocks: im::HashMap<H256, Block<TransactionVariant>>,
+    pub(crate) block_hashes: im::HashMap<u64, H256>,
     pub(crate) filters: EthFilters,
     pub(crate) impersonated_accounts: HashSet<Address>,
-    pub(crate) rich_accounts: HashSet<H160>,
-    pub(crate) previous_states: IndexMap<H256, HashMap<StorageKey, StorageValue>>,
+    pub(crate) rich_accounts: IndexSet<H160>,
+    pub(crate) previous_states: IndexMap<H256, im::HashMap<StorageKey, StorageValue>>,
     pub(crate) raw_storage: InMemoryStorage,
-    pub(crate) value_read_cache: HashMap<StorageKey, H256>,
-    pub(crate) factory_dep_cache: HashMap<H256, Option<Vec<u8>>>,
+    pub(crate) journal: HashMap<StorageKey, StorageValue>,
+    pub(crate) value_read_cache: im::HashMap<StorageKey, H256>,
+    pub(crate) factory_dep_cache: im::HashMap<H256, Option<Vec<u8>>>,
 }
 
 /// In-memory node, that can be used for local & unit testing.
@@ -866,9 +1120,17 @@ pub struct InMemoryNode<S: Clone> {
     pub(crate) inner: Arc<RwLock<InMemoryNodeInner<S>>>,
     /// List of snapshots of the [InMemoryNodeInner]. This is bounded at runtime by [MAX_SNAPSHOTS].
     pub(crate) snapshots: Arc<RwLock<Vec<Snapshot>>>,
+    /// Named snapshots of the [InMemoryNodeInner], which are not consumed when reverted to.
+    pub(crate) named_snapshots: Arc<RwLock<HashMap<String, Snapshot>>>,
+    /// If set, named snapshots are also written to this directory, so they survive restarts.
+    pub(crate) snapshot_dir: Option<PathBuf>,
     /// Configuration option that survives reset.
     #[allow(dead_code)]
     pub(crate) system_contracts_options: system_contracts::Options,
+    /// Accounts funded on start and on reset, whose private keys are known to the node.
+    pub(crate) dev_accounts: Vec<DevAccount>,
+    /// The balance the dev accounts, and the ones funded via [InMemoryNode::set_rich_account], start with.
+    pub(crate) dev_account_balance: U256,
 }
 
 fn contract_address_from_tx_result(execution_result: &VmExecutionResultAndLogs) -> Option<H160> {
@@ -900,10 +1162,29 @@ impl<S: ForkSource + std::fmt::Debug + Clone> InMemoryNode<S> {
         InMemoryNode {
             inner: Arc::new(RwLock::new(inner)),
             snapshots: Default::default(),
+            named_snapshots: Default::default(),
+            snapshot_dir: None,
             system_contracts_options,
+            dev_accounts: AccountsConfig::default()
+                .dev_accounts()
+                .expect("invalid rich wallets"),
+            dev_account_balance: AccountsConfig::default().balance_wei(),
         }
     }
 
+    /// Sets the directory named snapshots are written to, so they survive restarts.
+    pub fn with_snapshot_dir(mut self, snapshot_dir: Option<PathBuf>) -> Self {
+        self.snapshot_dir = snapshot_dir;
+        self
+    }
+
+    /// Sets the accounts funded on start and on reset, and the balance they start with.
+    pub fn with_dev_accounts(mut self, dev_accounts: Vec<DevAccount>, balance: U256) -> Self {
+        self.dev_accounts = dev_accounts;
+        self.dev_account_balance = balance;
+        self
+    }
+
     pub fn get_inner(&self) -> Arc<RwLock<InMemoryNodeInner<S>>> {
         self.inner.clone()
     }
@@ -951,16 +1232,16 @@ impl<S: ForkSource + std::fmt::Debug + Clone> InMemoryNode<S> {
     }
 
     pub fn reset(&self, fork: Option<ForkDetails>) -> Result<(), String> {
-        let observability = self
+        let (observability, subscriptions) = self
             .inner
             .read()
-            .map_err(|e| format!("Failed to acquire read lock: {}", e))?
-            .observability
-            .clone();
+            .map(|reader| (reader.observability.clone(), reader.subscriptions.clone()))
+            .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
 
         let config = self.get_config()?;
         let gas_values = self.get_gas_values()?;
-        let inner = InMemoryNodeInner::new(fork, observability, config, Some(gas_values));
+        let mut inner = InMemoryNodeInner::new(fork, observability, config, Some(gas_values));
+        inner.subscriptions = subscriptions;
 
         let mut writer = self
             .snapshots
@@ -976,14 +1257,35 @@ impl<S: ForkSource + std::fmt::Debug + Clone> InMemoryNode<S> {
             *guard = inner;
         }
 
-        for wallet in LEGACY_RICH_WALLETS.iter() {
-            let address = wallet.0;
-            self.set_rich_account(H160::from_str(address).unwrap());
-        }
-        for wallet in RICH_WALLETS.iter() {
-            let address = wallet.0;
-            self.set_rich_account(H160::from_str(address).unwrap());
+        self.fund_dev_accounts();
+        Ok(())
+    }
+
+    /// Resets the node to a previously produced local block, keeping the blocks, transactions and state up to it.
+    /// Like [InMemoryNode::reset], it discards the pending transactions and the `evm_snapshot` snapshots.
+    ///
+    /// The state of the block must still be archived, see [InMemoryNodeInner::rollback_to].
+    pub fn reset_to_block(&self, block_number: u64) -> Result<(), String> {
+        let mut snapshots = self
+            .snapshots
+            .write()
+            .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
+
+        {
+            let mut guard = self
+                .inner
+                .write()
+                .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
+            if block_number != guard.current_miniblock {
+                guard.rollback_to(block_number)?;
+            }
+            let dropped = guard.pool.clear();
+            for tx in &dropped {
+                guard.filters.notify_dropped_transaction(tx.hash());
+            }
         }
+
+        snapshots.clear();
         Ok(())
     }
 
@@ -998,7 +1300,43 @@ impl<S: ForkSource + std::fmt::Debug + Clone> InMemoryNode<S> {
         Ok(())
     }
 
-    /// Adds a lot of tokens to a given account.
+    /// Funds the dev accounts with their starting balance.
+    pub fn fund_dev_accounts(&self) {
+        for account in &self.dev_accounts {
+            self.set_rich_account(account.address);
+        }
+    }
+
+    /// Returns the private key of the given dev account, which the node signs with on its behalf.
+    pub fn dev_account_private_key(&self, address: H160) -> Result<K256PrivateKey, String> {
+        let account = self
+            .dev_accounts
+            .iter()
+            .find(|account| account.address == address)
+            .ok_or_else(|| format!("no private key is known for account {:?}", address))?;
+        K256PrivateKey::from_bytes(account.private_key)
+            .map_err(|err| format!("invalid private key of account {:?}: {}", address, err))
+    }
+
+    /// Signs an EIP-191 personal message on behalf of the given dev account.
+    pub fn sign_message(
+        &self,
+        address: H160,
+        message: &[u8],
+    ) -> Result<PackedEthSignature, String> {
+        let private_key = self.dev_account_private_key(address)?;
+        PackedEthSignature::sign(&private_key, message)
+            .map_err(|err| format!("failed signing message: {}", err))
+    }
+
+    /// Signs an already hashed message, like EIP-712 typed data, on behalf of the given dev account.
+    pub fn sign_hash(&self, address: H160, hash: H256) -> Result<PackedEthSignature, String> {
+        let private_key = self.dev_account_private_key(address)?;
+        PackedEthSignature::sign_raw(&private_key, &hash)
+            .map_err(|err| format!("failed signing message: {}", err))
+    }
+
+    /// Sets the balance of a given account to the starting balance of the dev accounts.
     pub fn set_rich_account(&self, address: H160) {
         let key = storage_key_for_eth_balance(&address);
 
@@ -1012,7 +1350,7 @@ impl<S: ForkSource + std::fmt::Debug + Clone> InMemoryNode<S> {
 
         let keys = {
             let mut storage_view = StorageView::new(&inner.fork_storage);
-            storage_view.set_value(key, u256_to_h256(U256::from(10u128.pow(30))));
+            storage_view.set_value(key, u256_to_h256(self.dev_account_balance));
             storage_view.modified_storage_keys().clone()
         };
 
@@ -1023,7 +1361,11 @@ impl<S: ForkSource + std::fmt::Debug + Clone> InMemoryNode<S> {
     }
 
     /// Runs L2 'eth call' method - that doesn't commit to a block.
-    pub fn run_l2_call(&self, mut l2_tx: L2Tx) -> Result<ExecutionResult, String> {
+    pub fn run_l2_call(
+        &self,
+        mut l2_tx: L2Tx,
+        block: Option<BlockIdVariant>,
+    ) -> Result<ExecutionResult, String> {
         let execution_mode = TxExecutionMode::EthCall;
 
         let inner = self
@@ -1031,7 +1373,9 @@ impl<S: ForkSource + std::fmt::Debug + Clone> InMemoryNode<S> {
             .write()
             .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
 
-        let storage = StorageView::new(&inner.fork_storage).into_rc_ptr();
+        let state = inner.archived_state_at(block)?;
+        let storage =
+            StorageView::new(HistoricalStorage::new(&inner.fork_storage, state)).into_rc_ptr();
 
         let bootloader_code = inner.system_contracts.contracts_for_l2_call();
 
@@ -1271,12 +1615,25 @@ impl<S: ForkSource + std::fmt::Debug + Clone> InMemoryNode<S> {
             return Err("exceeds block gas limit".into());
         }
 
-        let l2_gas_price = self
-            .inner
-            .read()
-            .expect("failed acquiring reader")
-            .fee_input_provider
-            .l2_gas_price;
+        let (l2_gas_price, block_gas_limit) = {
+            let inner = self.inner.read().expect("failed acquiring reader");
+            (
+                inner.fee_input_provider.l2_gas_price,
+                inner.config.block_gas_limit,
+            )
+        };
+        if tx.common_data.fee.gas_limit > block_gas_limit.into() {
+            tracing::info!(
+                "Submitted Tx is Unexecutable {:?} because of GasLimitExceedsBlockGasLimit {}",
+                tx.hash(),
+                tx.common_data.fee.gas_limit
+            );
+            return Err(format!(
+                "gas limit {} exceeds block gas limit {}",
+                tx.common_data.fee.gas_limit, block_gas_limit
+            ));
+        }
+
         if tx.common_data.fee.max_fee_per_gas < l2_gas_price.into() {
             tracing::info!(
                 "Submitted Tx is Unexecutable {:?} because of MaxFeePerGasTooLow {}",
@@ -1297,84 +1654,15 @@ impl<S: ForkSource + std::fmt::Debug + Clone> InMemoryNode<S> {
         Ok(())
     }
 
-    /// Executes the given L2 transaction and returns all the VM logs.
-    /// The bootloader can be omitted via specifying the `execute_bootloader` boolean.
-    /// This causes the VM to produce 1 L2 block per L1 block, instead of the usual 2 blocks per L1 block.
-    ///
-    /// **NOTE**
-    ///
-    /// This function must only rely on data populated initially via [ForkDetails]:
-    ///     * [InMemoryNodeInner::current_timestamp]
-    ///     * [InMemoryNodeInner::current_batch]
-    ///     * [InMemoryNodeInner::current_miniblock]
-    ///     * [InMemoryNodeInner::current_miniblock_hash]
-    ///     * [InMemoryNodeInner::fee_input_provider]
-    ///
-    /// And must _NEVER_ rely on data updated in [InMemoryNodeInner] during previous runs:
-    /// (if used, they must never panic and/or have meaningful defaults)
-    ///     * [InMemoryNodeInner::block_hashes]
-    ///     * [InMemoryNodeInner::blocks]
-    ///     * [InMemoryNodeInner::tx_results]
-    ///
-    /// This is because external users of the library may call this function to perform an isolated
-    /// VM operation (optionally without bootloader execution) with an external storage and get the results back.
-    /// So any data populated in [Self::run_l2_tx] will not be available for the next invocation.
-    pub fn run_l2_tx_raw(
+    /// Prints the summary of an executed transaction, including its console logs, call traces and events.
+    fn display_tx_summary(
         &self,
-        l2_tx: L2Tx,
-        execution_mode: TxExecutionMode,
-        mut tracers: Vec<
-            TracerPointer<StorageView<ForkStorage<S>>, multivm::vm_latest::HistoryDisabled>,
-        >,
-        execute_bootloader: bool,
-    ) -> Result<L2TxResult, String> {
-        let inner = self
-            .inner
-            .read()
-            .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
-
-        let storage = StorageView::new(inner.fork_storage.clone()).into_rc_ptr();
-
-        let (batch_env, block_ctx) = inner.create_l1_batch_env(storage.clone());
-
-        let bootloader_code = {
-            if inner
-                .impersonated_accounts
-                .contains(&l2_tx.common_data.initiator_address)
-            {
-                tracing::info!(
-                    "🕵️ Executing tx from impersonated account {:?}",
-                    l2_tx.common_data.initiator_address
-                );
-                inner.system_contracts.contracts(execution_mode, true)
-            } else {
-                inner.system_contracts.contracts(execution_mode, false)
-            }
-        };
-        let system_env = inner.create_system_env(bootloader_code.clone(), execution_mode);
-
-        let mut vm: Vm<_, HistoryDisabled> =
-            Vm::new(batch_env.clone(), system_env, storage.clone());
-
-        let tx: Transaction = l2_tx.clone().into();
-
-        vm.push_transaction(tx.clone());
-
-        let call_tracer_result = Arc::new(OnceCell::default());
-        let bootloader_debug_result = Arc::new(OnceCell::default());
-
-        tracers.push(CallTracer::new(call_tracer_result.clone()).into_tracer_pointer());
-        tracers.push(
-            BootloaderDebugTracer {
-                result: bootloader_debug_result.clone(),
-            }
-            .into_tracer_pointer(),
-        );
-
-        let tx_result = vm.inspect(tracers.into(), VmExecutionMode::OneTx);
-
-        let call_traces = call_tracer_result.get().unwrap();
-
+        inner: &InMemoryNodeInner<S>,
+        tx: &Transaction,
+        tx_result: &VmExecutionResultAndLogs,
+        call_traces: &[Call],
+        bootloader_debug_result: Option<&eyre::Result<BootloaderDebug, String>>,
+    ) {
         let spent_on_pubdata =
             tx_result.statistics.gas_used - tx_result.statistics.computational_gas_used as u64;
 
@@ -1403,7 +1691,7 @@ impl<S: ForkSource + std::fmt::Debug + Clone> InMemoryNode<S> {
             ),
             ShowGasDetails::All => {
                 let info =
-                    self.display_detailed_gas_info(bootloader_debug_result.get(), spent_on_pubdata);
+                    self.display_detailed_gas_info(bootloader_debug_result, spent_on_pubdata);
                 if info.is_err() {
                     tracing::info!(
                         "{}\nError: {}",
@@ -1415,11 +1703,11 @@ impl<S: ForkSource + std::fmt::Debug + Clone> InMemoryNode<S> {
         }
 
         if inner.config.show_storage_logs != ShowStorageLogs::None {
-            print_storage_logs_details(&inner.config.show_storage_logs, &tx_result);
+            print_storage_logs_details(&inner.config.show_storage_logs, tx_result);
         }
 
         if inner.config.show_vm_details != ShowVMDetails::None {
-            formatter::print_vm_details(&tx_result);
+            formatter::print_vm_details(tx_result);
         }
 
         tracing::info!("");
@@ -1458,33 +1746,119 @@ impl<S: ForkSource + std::fmt::Debug + Clone> InMemoryNode<S> {
         for event in &tx_result.logs.events {
             formatter::print_event(event, inner.config.resolve_hashes);
         }
+    }
 
-        // The computed block hash here will be different than that in production.
-        let hash = compute_hash(block_ctx.miniblock, l2_tx.hash());
-
-        let mut transaction = zksync_types::api::Transaction::from(l2_tx);
-        transaction.block_hash = Some(inner.current_miniblock_hash);
-        transaction.block_number = Some(U64::from(inner.current_miniblock));
+    /// Executes the given L2 transaction and returns all the VM logs.
+    /// The bootloader can be omitted via specifying the `execute_bootloader` boolean.
+    /// This causes the VM to produce 1 L2 block per L1 block, instead of the usual 2 blocks per L1 block.
+    ///
+    /// **NOTE**
+    ///
+    /// This function must only rely on data populated initially via [ForkDetails]:
+    ///     * [InMemoryNodeInner::current_timestamp]
+    ///     * [InMemoryNodeInner::current_batch]
+    ///     * [InMemoryNodeInner::current_miniblock]
+    ///     * [InMemoryNodeInner::current_miniblock_hash]
+    ///     * [InMemoryNodeInner::fee_input_provider]
+    ///
+    /// And must _NEVER_ rely on data updated in [InMemoryNodeInner] during previous runs:
+    /// (if used, they must never panic and/or have meaningful defaults)
+    ///     * [InMemoryNodeInner::block_hashes]
+    ///     * [InMemoryNodeInner::blocks]
+    ///     * [InMemoryNodeInner::tx_results]
+    ///
+    /// This is because external users of the library may call this function to perform an isolated
+    /// VM operation (optionally without bootloader execution) with an external storage and get the results back.
+    /// So any data populated in [Self::run_l2_tx] will not be available for the next invocation.
+    pub fn run_l2_tx_raw(
+        &self,
+        l2_tx: L2Tx,
+        execution_mode: TxExecutionMode,
+        mut tracers: Vec<
+            TracerPointer<StorageView<ForkStorage<S>>, multivm::vm_latest::HistoryDisabled>,
+        >,
+        execute_bootloader: bool,
+    ) -> Result<L2TxResult, String> {
+        let inner = self
+            .inner
+            .read()
+            .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
 
-        let parent_block_hash = inner
-            .block_hashes
-            .get(&(block_ctx.miniblock - 1))
-            .cloned()
-            .unwrap_or_default();
+        let storage = StorageView::new(inner.fork_storage.clone()).into_rc_ptr();
 
-        let block = Block {
-            hash,
-            parent_hash: parent_block_hash,
-            number: U64::from(block_ctx.miniblock),
-            timestamp: U256::from(batch_env.timestamp),
-            l1_batch_number: Some(U64::from(batch_env.number.0)),
-            transactions: vec![TransactionVariant::Full(transaction)],
-            gas_used: U256::from(tx_result.statistics.gas_used),
-            gas_limit: U256::from(BATCH_GAS_LIMIT),
-            ..Default::default()
-        };
+        let (batch_env, block_ctx) = inner.create_l1_batch_env(storage.clone());
 
-        let mut bytecodes = HashMap::new();
+        let bootloader_code = {
+            if inner
+                .impersonated_accounts
+                .contains(&l2_tx.common_data.initiator_address)
+            {
+                tracing::info!(
+                    "🕵️ Executing tx from impersonated account {:?}",
+                    l2_tx.common_data.initiator_address
+                );
+                inner.system_contracts.contracts(execution_mode, true)
+            } else {
+                inner.system_contracts.contracts(execution_mode, false)
+            }
+        };
+        let system_env = inner.create_system_env(bootloader_code.clone(), execution_mode);
+
+        let mut vm: Vm<_, HistoryDisabled> =
+            Vm::new(batch_env.clone(), system_env, storage.clone());
+
+        let tx: Transaction = l2_tx.clone().into();
+
+        vm.push_transaction(tx.clone());
+
+        let call_tracer_result = Arc::new(OnceCell::default());
+        let bootloader_debug_result = Arc::new(OnceCell::default());
+
+        tracers.push(CallTracer::new(call_tracer_result.clone()).into_tracer_pointer());
+        tracers.push(
+            BootloaderDebugTracer {
+                result: bootloader_debug_result.clone(),
+            }
+            .into_tracer_pointer(),
+        );
+
+        let tx_result = vm.inspect(tracers.into(), VmExecutionMode::OneTx);
+
+        let call_traces = call_tracer_result.get().unwrap();
+        self.display_tx_summary(
+            &inner,
+            &tx,
+            &tx_result,
+            call_traces,
+            bootloader_debug_result.get(),
+        );
+
+        // The computed block hash here will be different than that in production.
+        let hash = compute_hash(block_ctx.miniblock, l2_tx.hash());
+
+        let mut transaction = zksync_types::api::Transaction::from(l2_tx);
+        transaction.block_hash = Some(inner.current_miniblock_hash);
+        transaction.block_number = Some(U64::from(inner.current_miniblock));
+
+        let parent_block_hash = inner
+            .block_hashes
+            .get(&(block_ctx.miniblock - 1))
+            .cloned()
+            .unwrap_or_default();
+
+        let block = Block {
+            hash,
+            parent_hash: parent_block_hash,
+            number: U64::from(block_ctx.miniblock),
+            timestamp: U256::from(batch_env.timestamp),
+            l1_batch_number: Some(U64::from(batch_env.number.0)),
+            transactions: vec![TransactionVariant::Full(transaction)],
+            gas_used: U256::from(tx_result.statistics.gas_used),
+            gas_limit: U256::from(inner.config.block_gas_limit),
+            ..Default::default()
+        };
+
+        let mut bytecodes = HashMap::new();
         for b in vm.get_last_tx_compressed_bytecodes().iter() {
             let hashcode = match bytecode_to_factory_dep(b.original.clone()) {
                 Ok(hc) => hc,
@@ -1511,51 +1885,442 @@ impl<S: ForkSource + std::fmt::Debug + Clone> InMemoryNode<S> {
         ))
     }
 
-    /// Runs L2 transaction and commits it to a new block.
-    pub fn run_l2_tx(&self, l2_tx: L2Tx, execution_mode: TxExecutionMode) -> Result<(), String> {
+    /// Submits L2 transaction to the node.
+    ///
+    /// Transactions with a nonce gap are queued until the missing nonces are submitted. Otherwise, if automine
+    /// is enabled, the transaction is executed and committed to a new block right away, together with the
+    /// queued transactions it unblocked. If automine is disabled, it is kept in the pending pool until the
+    /// next block is mined.
+    pub fn submit_l2_tx(&self, l2_tx: L2Tx) -> Result<(), String> {
         let tx_hash = l2_tx.hash();
-        let transaction_type = l2_tx.common_data.transaction_type;
+        self.validate_l2_tx(&l2_tx)?;
 
-        tracing::info!("");
-        tracing::info!("Validating {}", format!("{:?}", tx_hash).bold());
+        let mut inner = self
+            .inner
+            .write()
+            .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
+        let account_nonce = inner.account_nonce(l2_tx.initiator_account())?;
+        let added = inner.pool.add(l2_tx, account_nonce)?;
+        inner.notify_new_pending_transaction(tx_hash);
+        if let Some(replaced_hash) = added.replaced {
+            inner.filters.notify_dropped_transaction(replaced_hash);
+            tracing::info!("Transaction {:?} was replaced", replaced_hash);
+        }
+        if added.queued {
+            tracing::info!(
+                "Transaction {} queued until the preceding nonces are submitted",
+                format!("{:?}", tx_hash).bold()
+            );
+            return Ok(());
+        }
 
-        match self.validate_tx(&l2_tx) {
-            Ok(_) => (),
-            Err(e) => {
-                return Err(e);
+        tracing::info!(
+            "Transaction {} added to the pending pool",
+            format!("{:?}", tx_hash).bold()
+        );
+        for promoted_hash in &added.promoted {
+            tracing::info!(
+                "Queued transaction {:?} promoted to the pending pool",
+                promoted_hash
+            );
+        }
+        if !inner.automine {
+            return Ok(());
+        }
+
+        // with automine the pending pool only holds the submitted transaction, followed by
+        // the queued transactions it unblocked, and each of them is mined in its own block
+        let txs = inner.pool.take_all();
+        drop(inner);
+
+        let mut result = Ok(());
+        for tx in txs {
+            let hash = tx.hash();
+            if let Some((_, e)) = self
+                .seal_block(vec![tx], TxExecutionMode::VerifyExecute)?
+                .pop()
+            {
+                if hash == tx_hash {
+                    result = Err(e);
+                } else {
+                    tracing::error!(
+                        "Dropping queued transaction {:?}, execution failed: {}",
+                        hash,
+                        e
+                    );
+                }
             }
-        };
+        }
 
-        tracing::info!("Executing {}", format!("{:?}", tx_hash).bold());
+        result
+    }
+
+    /// Executes all the transactions from the pending pool and commits them to a new block.
+    /// Transactions that fail to execute are dropped.
+    ///
+    /// Returns the number of transactions that were sealed.
+    pub fn mine_pending_txs(&self) -> Result<usize, String> {
+        self.mine_pending_txs_with(vec![])
+    }
+
+    /// Executes all the transactions from the pending pool, followed by the given transactions, and commits
+    /// them to a new block. Transactions that fail to execute are dropped, while an invalid transaction
+    /// among the given ones fails the whole call before anything is mined.
+    ///
+    /// Returns the number of transactions that were sealed.
+    pub fn mine_pending_txs_with(&self, extra_txs: Vec<L2Tx>) -> Result<usize, String> {
+        for tx in &extra_txs {
+            self.validate_l2_tx(tx)?;
+        }
+
+        let mut inner = self
+            .inner
+            .write()
+            .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
+        self.mine_pending_txs_locked(&mut inner, extra_txs)
+    }
+
+    /// Same as [InMemoryNode::mine_pending_txs_with], but runs under an already acquired write lock, and expects
+    /// the given transactions to be validated already.
+    pub(crate) fn mine_pending_txs_locked(
+        &self,
+        inner: &mut InMemoryNodeInner<S>,
+        extra_txs: Vec<L2Tx>,
+    ) -> Result<usize, String> {
+        for tx in &extra_txs {
+            inner.notify_new_pending_transaction(tx.hash());
+        }
+        let mut txs = inner.pool.take_all();
+        txs.extend(extra_txs);
+        if txs.is_empty() {
+            return Ok(0);
+        }
+
+        let total = txs.len();
+        let mut sealed = vec![];
+        let rejected =
+            self.seal_block_with(inner, txs, TxExecutionMode::VerifyExecute, &mut sealed);
+        inner.notify_sealed_blocks(sealed);
+        let rejected = rejected?;
+        for (tx_hash, e) in rejected.iter() {
+            tracing::error!(
+                "Dropping transaction {:?}, execution failed: {}",
+                tx_hash,
+                e
+            );
+        }
+
+        Ok(total - rejected.len())
+    }
+
+    /// Validates L2 transaction before it is accepted by the node.
+    pub(crate) fn validate_l2_tx(&self, l2_tx: &L2Tx) -> Result<(), String> {
+        tracing::info!("");
+        tracing::info!("Validating {}", format!("{:?}", l2_tx.hash()).bold());
+
+        self.validate_tx(l2_tx)
+    }
+
+    /// Runs L2 transaction and commits it to a new block.
+    pub fn run_l2_tx(&self, l2_tx: L2Tx, execution_mode: TxExecutionMode) -> Result<(), String> {
+        self.validate_l2_tx(&l2_tx)?;
 
         {
             let mut inner = self
                 .inner
                 .write()
                 .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
-            inner.filters.notify_new_pending_transaction(tx_hash);
+            inner.notify_new_pending_transaction(l2_tx.hash());
         }
 
-        let (keys, result, call_traces, block, bytecodes, block_ctx) =
-            self.run_l2_tx_raw(l2_tx.clone(), execution_mode, vec![], true)?;
+        match self.seal_block(vec![l2_tx], execution_mode)?.pop() {
+            Some((_, e)) => Err(e),
+            None => Ok(()),
+        }
+    }
 
-        if let ExecutionResult::Halt { reason } = result.result {
-            // Halt means that something went really bad with the transaction execution (in most cases invalid signature,
-            // but it could also be bootloader panic etc).
-            // In such case, we should not persist the VM data, and we should pretend that transaction never existed.
-            return Err(format!("Transaction HALT: {}", reason));
+    /// Executes a bundle of L2 transactions atomically, committing all of them to a single new block.
+    ///
+    /// If any of the transactions fails or reverts, or they do not fit into a single block, the node state
+    /// is restored to how it was before the bundle, so none of the transactions is included.
+    ///
+    /// Returns the hashes of the transactions in the bundle.
+    pub fn run_l2_tx_bundle(&self, txs: Vec<L2Tx>) -> Result<Vec<H256>, String> {
+        if txs.is_empty() {
+            return Err("bundle contains no transactions".into());
         }
-        // Write all the mutated keys (storage slots).
+        for tx in &txs {
+            self.validate_l2_tx(tx)?;
+        }
+
+        let tx_hashes = txs.iter().map(|tx| tx.hash()).collect::<Vec<_>>();
+
+        // the write lock is held until the bundle is either committed or reverted, so other requests never
+        // observe, or build upon, a partially applied bundle
         let mut inner = self
             .inner
             .write()
             .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
+        let snapshot = inner.snapshot()?;
+
+        let mut sealed = vec![];
+        let result = self
+            .seal_block_with(&mut inner, txs, TxExecutionMode::VerifyExecute, &mut sealed)
+            .and_then(|rejected| match rejected.first() {
+                Some((tx_hash, e)) => Err(format!("transaction {:?} failed: {}", tx_hash, e)),
+                None => Self::ensure_bundle_committed(&inner, &tx_hashes),
+            });
+        if let Err(e) = result {
+            tracing::error!("Reverting bundle: {}", e);
+            inner.restore_snapshot(snapshot)?;
+            return Err(e);
+        }
+
+        for tx_hash in &tx_hashes {
+            inner.notify_new_pending_transaction(*tx_hash);
+        }
+        inner.notify_sealed_blocks(sealed);
+
+        Ok(tx_hashes)
+    }
+
+    /// Checks that all the transactions of a bundle succeeded, and were committed to the same block.
+    fn ensure_bundle_committed(
+        inner: &InMemoryNodeInner<S>,
+        tx_hashes: &[H256],
+    ) -> Result<(), String> {
+        let mut bundle_block = None;
+        for tx_hash in tx_hashes {
+            let tx_result = inner
+                .tx_results
+                .get(tx_hash)
+                .ok_or_else(|| format!("transaction {:?} was not executed", tx_hash))?;
+            if let ExecutionResult::Revert { output } = &tx_result.info.result.result {
+                return Err(format!("transaction {:?} reverted: {}", tx_hash, output));
+            }
+            let block = tx_result.info.miniblock_number;
+            if *bundle_block.get_or_insert(block) != block {
+                return Err("transactions do not fit into a single block".into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Executes already validated L2 transactions and commits them to new blocks.
+    ///
+    /// Consecutive transactions share the same L1 batch and miniblock, unless they need a different bootloader:
+    /// transactions from impersonated accounts are executed without account validation, so they are never
+    /// batched together with regular transactions. Each batch is followed by a fictive empty block.
+    ///
+    /// Transactions that halt are excluded from the block, and are returned together with the halt reason.
+    pub fn seal_block(
+        &self,
+        txs: Vec<L2Tx>,
+        execution_mode: TxExecutionMode,
+    ) -> Result<Vec<(H256, String)>, String> {
+        let mut inner = self
+            .inner
+            .write()
+            .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
+
+        let mut sealed = vec![];
+        let result = self.seal_block_with(&mut inner, txs, execution_mode, &mut sealed);
+        inner.notify_sealed_blocks(sealed);
+
+        result
+    }
+
+    /// Same as [InMemoryNode::seal_block], but runs under an already acquired write lock, and collects the
+    /// committed blocks into `sealed` instead of notifying the filters and subscriptions, so the caller can
+    /// do it once the changes are final.
+    fn seal_block_with(
+        &self,
+        inner: &mut InMemoryNodeInner<S>,
+        txs: Vec<L2Tx>,
+        execution_mode: TxExecutionMode,
+        sealed: &mut Vec<SealedBlock>,
+    ) -> Result<Vec<(H256, String)>, String> {
+        let impersonated_accounts = inner.impersonated_accounts.clone();
+
+        let mut batches: Vec<(bool, Vec<L2Tx>)> = vec![];
+        for tx in txs {
+            let impersonating = impersonated_accounts.contains(&tx.common_data.initiator_address);
+            match batches.last_mut() {
+                Some((batch_impersonating, batch_txs)) if *batch_impersonating == impersonating => {
+                    batch_txs.push(tx)
+                }
+                _ => batches.push((impersonating, vec![tx])),
+            }
+        }
+
+        let mut rejected = vec![];
+        for (impersonating, mut batch_txs) in batches {
+            // transactions that don't fit into a batch are moved to the next one
+            while !batch_txs.is_empty() {
+                let (batch_rejected, deferred) =
+                    self.seal_batch(inner, batch_txs, impersonating, execution_mode, sealed)?;
+                rejected.extend(batch_rejected);
+                batch_txs = deferred;
+            }
+        }
+
+        Ok(rejected)
+    }
+
+    /// Executes the given transactions in a single VM instance, and commits them to a new L1 batch
+    /// consisting of one miniblock with all the transactions, and the fictive block that closes the batch.
+    ///
+    /// The batch is sealed early once it meets the configured [crate::config::batch::SealCriteria], in which case the remaining
+    /// transactions are returned to be executed in the next batch. Transactions that exceed the criteria on their
+    /// own can never be included in a batch, and are rejected.
+    fn seal_batch(
+        &self,
+        inner: &mut InMemoryNodeInner<S>,
+        txs: Vec<L2Tx>,
+        impersonating: bool,
+        execution_mode: TxExecutionMode,
+        sealed: &mut Vec<SealedBlock>,
+    ) -> Result<(Vec<(H256, String)>, Vec<L2Tx>), String> {
+        let storage = StorageView::new(inner.fork_storage.clone()).into_rc_ptr();
+        let (batch_env, block_ctx) = inner.create_l1_batch_env(storage.clone());
+        let bootloader_code = inner
+            .system_contracts
+            .contracts(execution_mode, impersonating);
+        let system_env = inner.create_system_env(bootloader_code.clone(), execution_mode);
+
+        let mut vm: Vm<_, HistoryEnabled> = Vm::new(batch_env.clone(), system_env, storage.clone());
+
+        let seal_criteria = inner.config.seal_criteria;
+        let block_gas_limit = inner.config.block_gas_limit;
+        let mut usage = BatchUsage::default();
+        let mut executed = vec![];
+        let mut rejected = vec![];
+        let mut deferred = vec![];
+        let mut txs = txs.into_iter();
+        while let Some(l2_tx) = txs.next() {
+            let tx_hash = l2_tx.hash();
+            tracing::info!("Executing {}", format!("{:?}", tx_hash).bold());
+            if impersonating {
+                tracing::info!(
+                    "🕵️ Executing tx from impersonated account {:?}",
+                    l2_tx.common_data.initiator_address
+                );
+            }
+
+            let tx: Transaction = l2_tx.clone().into();
+            vm.make_snapshot();
+            vm.push_transaction(tx.clone());
+
+            let call_tracer_result = Arc::new(OnceCell::default());
+            let bootloader_debug_result = Arc::new(OnceCell::default());
+            let tracers: Vec<TracerPointer<StorageView<ForkStorage<S>>, HistoryEnabled>> = vec![
+                CallTracer::new(call_tracer_result.clone()).into_tracer_pointer(),
+                BootloaderDebugTracer {
+                    result: bootloader_debug_result.clone(),
+                }
+                .into_tracer_pointer(),
+            ];
+            let tx_result = vm.inspect(tracers.into(), VmExecutionMode::OneTx);
+
+            let call_traces: Vec<Call> = call_tracer_result.get().cloned().unwrap_or_default();
+
+            if let ExecutionResult::Halt { reason } = &tx_result.result {
+                metrics::observe_transaction(&tx_result.result, tx_result.statistics.gas_used);
+                self.display_tx_summary(
+                    inner,
+                    &tx,
+                    &tx_result,
+                    &call_traces,
+                    bootloader_debug_result.get(),
+                );
+                // Halt means that something went really bad with the transaction execution (in most cases invalid signature,
+                // but it could also be bootloader panic etc).
+                // In such case, we should not persist the VM data, and we should pretend that transaction never existed.
+                vm.rollback_to_the_latest_snapshot();
+                rejected.push((tx_hash, format!("Transaction HALT: {}", reason)));
+                continue;
+            }
+
+            let tx_usage = BatchUsage::for_tx(
+                &l2_tx,
+                tx_result.statistics.gas_used,
+                tx_result.statistics.pubdata_published as u64,
+            );
+            if let Some(limit) = usage
+                .add(&tx_usage)
+                .exceeded_limit(&seal_criteria, block_gas_limit)
+            {
+                vm.rollback_to_the_latest_snapshot();
+                if executed.is_empty() {
+                    tracing::info!("Transaction {:?} exceeds the {} limit", tx_hash, limit);
+                    rejected.push((tx_hash, format!("exceeds {} limit", limit)));
+                    continue;
+                }
+
+                tracing::info!(
+                    "Batch {} reached the {} limit, moving the remaining transactions to the next batch",
+                    batch_env.number.0,
+                    limit
+                );
+                deferred.push(l2_tx);
+                deferred.extend(txs);
+                break;
+            }
+            usage = usage.add(&tx_usage);
+            vm.pop_snapshot_no_rollback();
+
+            self.display_tx_summary(
+                inner,
+                &tx,
+                &tx_result,
+                &call_traces,
+                bootloader_debug_result.get(),
+            );
+
+            let mut bytecodes = HashMap::new();
+            for b in vm.get_last_tx_compressed_bytecodes().iter() {
+                let hashcode = match bytecode_to_factory_dep(b.original.clone()) {
+                    Ok(hc) => hc,
+                    Err(error) => {
+                        tracing::error!(
+                            "{}",
+                            format!("cannot convert bytecode: {}", error).on_red()
+                        );
+                        return Err(error.to_string());
+                    }
+                };
+                bytecodes.insert(hashcode.0, hashcode.1);
+            }
+
+            executed.push((l2_tx, tx_result, call_traces, bytecodes));
+        }
+
+        if executed.is_empty() {
+            // nothing to commit, the batch is discarded
+            return Ok((rejected, deferred));
+        }
+
+        vm.execute(VmExecutionMode::Bootloader);
+
+        let keys = storage.borrow().modified_storage_keys().clone();
+
+        // archive the state of the current block before it's modified by the batch
+        if let Err(err) = inner.archive_state() {
+            tracing::error!(
+                "failed archiving state for block {}: {}",
+                inner.current_miniblock,
+                err
+            );
+        }
+
+        // Write all the mutated keys (storage slots).
         for (key, value) in keys.iter() {
             inner.fork_storage.set_value(*key, *value);
         }
 
         // Write all the factory deps.
-        for (hash, code) in bytecodes.iter() {
+        for (hash, code) in executed.iter().flat_map(|(_, _, _, bytecodes)| bytecodes) {
             inner.fork_storage.store_factory_dep(
                 u256_to_h256(*hash),
                 code.iter()
@@ -1568,82 +2333,115 @@ impl<S: ForkSource + std::fmt::Debug + Clone> InMemoryNode<S> {
             )
         }
 
-        for (log_idx, event) in result.logs.events.iter().enumerate() {
-            inner.filters.notify_new_log(
-                &Log {
+        // The computed block hash here will be different than that in production.
+        let block_hash = compute_hash(block_ctx.miniblock, executed[0].0.hash());
+        let block_number = U64::from(block_ctx.miniblock);
+        let l1_batch_number = Some(U64::from(batch_env.number.0));
+        let parent_block_hash = inner
+            .block_hashes
+            .get(&(block_ctx.miniblock - 1))
+            .cloned()
+            .unwrap_or_default();
+        let effective_gas_price = Some(inner.fee_input_provider.l2_gas_price.into());
+
+        let mut transactions = Vec::with_capacity(executed.len());
+        let mut block_gas_used = 0;
+        let mut cumulative_gas_used = U256::zero();
+        let mut block_log_index = 0;
+        let mut block_logs = vec![];
+        for (tx_index, (l2_tx, result, call_traces, _)) in executed.into_iter().enumerate() {
+            let tx_hash = l2_tx.hash();
+            let transaction_index = U64::from(tx_index);
+            let gas_used = l2_tx.common_data.fee.gas_limit - result.refunds.gas_refunded;
+            cumulative_gas_used += gas_used;
+            block_gas_used += result.statistics.gas_used;
+            metrics::observe_transaction(&result.result, result.statistics.gas_used);
+
+            let logs = result
+                .logs
+                .events
+                .iter()
+                .enumerate()
+                .map(|(log_idx, event)| Log {
                     address: event.address,
                     topics: event.indexed_topics.clone(),
                     data: Bytes(event.value.clone()),
-                    block_hash: Some(block.hash),
-                    block_number: Some(block.number),
-                    l1_batch_number: block.l1_batch_number,
+                    block_hash: Some(block_hash),
+                    block_number: Some(block_number),
+                    l1_batch_number,
                     transaction_hash: Some(tx_hash),
-                    transaction_index: Some(U64::zero()),
-                    log_index: Some(U256::from(log_idx)),
+                    transaction_index: Some(transaction_index),
+                    log_index: Some(U256::from(block_log_index + log_idx)),
                     transaction_log_index: Some(U256::from(log_idx)),
                     log_type: None,
                     removed: Some(false),
+                })
+                .collect::<Vec<_>>();
+            block_log_index += logs.len();
+            block_logs.extend(logs.iter().cloned());
+
+            let tx_receipt = TransactionReceipt {
+                transaction_hash: tx_hash,
+                transaction_index,
+                block_hash,
+                block_number,
+                // every batch holds a single miniblock with transactions
+                l1_batch_tx_index: Some(transaction_index),
+                l1_batch_number,
+                from: l2_tx.initiator_account(),
+                to: Some(l2_tx.recipient_account()),
+                root: H256::zero(),
+                cumulative_gas_used,
+                gas_used: Some(gas_used),
+                contract_address: contract_address_from_tx_result(&result),
+                logs,
+                l2_to_l1_logs: vec![],
+                status: if result.result.is_failed() {
+                    U64::from(0)
+                } else {
+                    U64::from(1)
+                },
+                effective_gas_price,
+                transaction_type: Some((l2_tx.common_data.transaction_type as u32).into()),
+                logs_bloom: Default::default(),
+            };
+
+            let mut transaction = zksync_types::api::Transaction::from(l2_tx.clone());
+            transaction.block_hash = Some(block_hash);
+            transaction.block_number = Some(block_number);
+            transaction.transaction_index = Some(transaction_index);
+            transaction.l1_batch_number = l1_batch_number;
+            transaction.l1_batch_tx_index = Some(transaction_index);
+            transactions.push(TransactionVariant::Full(transaction));
+
+            let debug =
+                create_debug_output(&l2_tx, &result, call_traces).expect("create debug output"); // OK to unwrap here as Halt is handled above
+            inner.tx_results.insert(
+                tx_hash,
+                TransactionResult {
+                    info: TxExecutionInfo {
+                        tx: l2_tx,
+                        batch_number: batch_env.number.0,
+                        miniblock_number: block_ctx.miniblock,
+                        result,
+                    },
+                    receipt: tx_receipt,
+                    debug,
                 },
-                block.number,
             );
         }
-        let tx_receipt = TransactionReceipt {
-            transaction_hash: tx_hash,
-            transaction_index: U64::from(0),
-            block_hash: block.hash,
-            block_number: block.number,
-            l1_batch_tx_index: None,
-            l1_batch_number: block.l1_batch_number,
-            from: l2_tx.initiator_account(),
-            to: Some(l2_tx.recipient_account()),
-            root: H256::zero(),
-            cumulative_gas_used: Default::default(),
-            gas_used: Some(l2_tx.common_data.fee.gas_limit - result.refunds.gas_refunded),
-            contract_address: contract_address_from_tx_result(&result),
-            logs: result
-                .logs
-                .events
-                .iter()
-                .enumerate()
-                .map(|(log_idx, log)| Log {
-                    address: log.address,
-                    topics: log.indexed_topics.clone(),
-                    data: Bytes(log.value.clone()),
-                    block_hash: Some(block.hash),
-                    block_number: Some(block.number),
-                    l1_batch_number: block.l1_batch_number,
-                    transaction_hash: Some(tx_hash),
-                    transaction_index: Some(U64::zero()),
-                    log_index: Some(U256::from(log_idx)),
-                    transaction_log_index: Some(U256::from(log_idx)),
-                    log_type: None,
-                    removed: Some(false),
-                })
-                .collect(),
-            l2_to_l1_logs: vec![],
-            status: if result.result.is_failed() {
-                U64::from(0)
-            } else {
-                U64::from(1)
-            },
-            effective_gas_price: Some(inner.fee_input_provider.l2_gas_price.into()),
-            transaction_type: Some((transaction_type as u32).into()),
-            logs_bloom: Default::default(),
+
+        let block = Block {
+            hash: block_hash,
+            parent_hash: parent_block_hash,
+            number: block_number,
+            timestamp: U256::from(batch_env.timestamp),
+            l1_batch_number,
+            transactions,
+            gas_used: U256::from(block_gas_used),
+            gas_limit: U256::from(block_gas_limit),
+            ..Default::default()
         };
-        let debug = create_debug_output(&l2_tx, &result, call_traces).expect("create debug output"); // OK to unwrap here as Halt is handled above
-        inner.tx_results.insert(
-            tx_hash,
-            TransactionResult {
-                info: TxExecutionInfo {
-                    tx: l2_tx,
-                    batch_number: block.l1_batch_number.unwrap_or_default().as_u32(),
-                    miniblock_number: block.number.as_u64(),
-                    result,
-                },
-                receipt: tx_receipt,
-                debug,
-            },
-        );
 
         // With the introduction of 'l2 blocks' (and virtual blocks),
         // we are adding one l2 block at the end of each batch (to handle things like remaining events etc).
@@ -1655,6 +2453,7 @@ impl<S: ForkSource + std::fmt::Debug + Clone> InMemoryNode<S> {
             block_ctx.timestamp,
             block_ctx.batch,
             Some(parent_block_hash),
+            block_gas_limit,
         );
 
         inner.current_batch = inner.current_batch.saturating_add(1);
@@ -1663,13 +2462,16 @@ impl<S: ForkSource + std::fmt::Debug + Clone> InMemoryNode<S> {
             .into_iter()
             .enumerate()
         {
-            // archive current state before we produce new batch/blocks
-            if let Err(err) = inner.archive_state() {
-                tracing::error!(
-                    "failed archiving state for block {}: {}",
-                    inner.current_miniblock,
-                    err
-                );
+            // archive the state of the transactions block before producing the fictive block,
+            // the state of the previous block was archived before applying the batch
+            if i > 0 {
+                if let Err(err) = inner.archive_state() {
+                    tracing::error!(
+                        "failed archiving state for block {}: {}",
+                        inner.current_miniblock,
+                        err
+                    );
+                }
             }
 
             inner.current_miniblock = inner.current_miniblock.saturating_add(1);
@@ -1705,14 +2507,28 @@ impl<S: ForkSource + std::fmt::Debug + Clone> InMemoryNode<S> {
             let block_hash = block.hash;
             inner.current_miniblock_hash = block_hash;
             inner.block_hashes.insert(block.number.as_u64(), block.hash);
+            sealed.push(SealedBlock {
+                block: block.clone(),
+                logs: if i == 0 {
+                    std::mem::take(&mut block_logs)
+                } else {
+                    vec![]
+                },
+            });
             inner.blocks.insert(block.hash, block);
-            inner.filters.notify_new_block(block_hash);
         }
 
-        Ok(())
+        Ok((rejected, deferred))
     }
 }
 
+/// A block committed by [InMemoryNode::seal_block], together with the logs emitted by its transactions.
+#[derive(Debug)]
+struct SealedBlock {
+    block: Block<TransactionVariant>,
+    logs: Vec<Log>,
+}
+
 /// Keeps track of a block's batch number, miniblock number and timestamp.
 /// Useful for keeping track of the current context when creating multiple blocks.
 #[derive(Debug, Clone, Default)]
@@ -1834,9 +2650,420 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_seal_block_commits_multiple_txs_to_the_same_block_and_batch() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let txs = (1..=3)
+            .map(|i| {
+                let tx = testing::TransactionBuilder::new()
+                    .set_hash(H256::repeat_byte(i))
+                    .build();
+                node.set_rich_account(tx.common_data.initiator_address);
+                tx
+            })
+            .collect::<Vec<_>>();
+        let start_miniblock = node.get_inner().read().unwrap().current_miniblock;
+
+        let rejected = node
+            .seal_block(txs.clone(), TxExecutionMode::VerifyExecute)
+            .expect("failed sealing block");
+        assert!(rejected.is_empty());
+
+        let inner = node.get_inner();
+        let inner = inner.read().unwrap();
+        // one block with the transactions, and the fictive block closing the batch
+        assert_eq!(start_miniblock + 2, inner.current_miniblock);
+
+        let receipts = txs
+            .iter()
+            .map(|tx| inner.tx_results.get(&tx.hash()).unwrap().receipt.clone())
+            .collect::<Vec<_>>();
+        let mut cumulative_gas_used = U256::zero();
+        for (index, (tx, receipt)) in txs.iter().zip(&receipts).enumerate() {
+            cumulative_gas_used += receipt.gas_used.unwrap();
+            assert_eq!(tx.hash(), receipt.transaction_hash);
+            assert_eq!(U64::from(start_miniblock + 1), receipt.block_number);
+            assert_eq!(receipts[0].block_hash, receipt.block_hash);
+            assert_eq!(receipts[0].l1_batch_number, receipt.l1_batch_number);
+            assert_eq!(U64::from(index), receipt.transaction_index);
+            assert_eq!(Some(U64::from(index)), receipt.l1_batch_tx_index);
+            assert_eq!(cumulative_gas_used, receipt.cumulative_gas_used);
+        }
+
+        let block = inner.blocks.get(&receipts[0].block_hash).unwrap();
+        assert_eq!(3, block.transactions.len());
+    }
+
+    #[tokio::test]
+    async fn test_seal_block_moves_overflowing_txs_to_the_next_batch() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        node.get_inner()
+            .write()
+            .unwrap()
+            .config
+            .seal_criteria
+            .max_transactions = 2;
+        let txs = (1..=3)
+            .map(|i| {
+                let tx = testing::TransactionBuilder::new()
+                    .set_hash(H256::repeat_byte(i))
+                    .build();
+                node.set_rich_account(tx.common_data.initiator_address);
+                tx
+            })
+            .collect::<Vec<_>>();
+
+        let rejected = node
+            .seal_block(txs.clone(), TxExecutionMode::VerifyExecute)
+            .expect("failed sealing block");
+        assert!(rejected.is_empty());
+
+        let inner = node.get_inner();
+        let inner = inner.read().unwrap();
+        let receipts = txs
+            .iter()
+            .map(|tx| inner.tx_results.get(&tx.hash()).unwrap().receipt.clone())
+            .collect::<Vec<_>>();
+        for (tx, receipt) in txs.iter().zip(&receipts) {
+            assert_eq!(tx.hash(), receipt.transaction_hash);
+        }
+        assert_eq!(receipts[0].l1_batch_number, receipts[1].l1_batch_number);
+        assert_eq!(
+            receipts[0].l1_batch_number.unwrap() + 1,
+            receipts[2].l1_batch_number.unwrap()
+        );
+        assert_eq!(U64::zero(), receipts[2].transaction_index);
+    }
+
+    #[tokio::test]
+    async fn test_seal_block_rejects_txs_exceeding_seal_criteria() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        node.get_inner()
+            .write()
+            .unwrap()
+            .config
+            .seal_criteria
+            .max_gas = 1;
+        let tx = testing::TransactionBuilder::new().build();
+        node.set_rich_account(tx.common_data.initiator_address);
+
+        let result = node.run_l2_tx(tx.clone(), TxExecutionMode::VerifyExecute);
+
+        assert_eq!(result.err(), Some("exceeds batch gas limit".into()));
+        assert!(!node
+            .get_inner()
+            .read()
+            .unwrap()
+            .tx_results
+            .contains_key(&tx.hash()));
+    }
+
+    #[tokio::test]
+    async fn test_seal_block_rejects_txs_exceeding_block_gas_limit() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        node.get_inner().write().unwrap().config.block_gas_limit = 1;
+        let tx = testing::TransactionBuilder::new().build();
+        node.set_rich_account(tx.common_data.initiator_address);
+
+        let rejected = node
+            .seal_block(vec![tx.clone()], TxExecutionMode::VerifyExecute)
+            .expect("failed sealing block");
+
+        assert_eq!(
+            vec![(tx.hash(), "exceeds block gas limit".to_string())],
+            rejected
+        );
+    }
+
+    #[tokio::test]
+    async fn test_submit_l2_tx_rejects_gas_limit_exceeding_block_gas_limit() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        node.get_inner().write().unwrap().config.block_gas_limit = 1_000_000;
+        let tx = testing::TransactionBuilder::new()
+            .set_gas_limit(U256::from(1_000_001))
+            .build();
+        node.set_rich_account(tx.common_data.initiator_address);
+
+        let result = node.submit_l2_tx(tx.clone());
+
+        assert_eq!(
+            result.err(),
+            Some("gas limit 1000001 exceeds block gas limit 1000000".into())
+        );
+        assert!(node.get_inner().read().unwrap().pool.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_seal_block_moves_txs_exceeding_block_gas_limit_to_next_block() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let first_tx = testing::TransactionBuilder::new()
+            .set_hash(H256::repeat_byte(0x1))
+            .build();
+        node.set_rich_account(first_tx.common_data.initiator_address);
+        node.run_l2_tx(first_tx.clone(), TxExecutionMode::VerifyExecute)
+            .expect("failed running tx");
+        let gas_used = node
+            .get_inner()
+            .read()
+            .unwrap()
+            .tx_results
+            .get(&first_tx.hash())
+            .unwrap()
+            .receipt
+            .gas_used
+            .unwrap()
+            .as_u64();
+        // enough for a single transaction, but not for two of them
+        node.get_inner().write().unwrap().config.block_gas_limit = gas_used * 3 / 2;
+        let txs = (2..=3)
+            .map(|i| {
+                let tx = testing::TransactionBuilder::new()
+                    .set_hash(H256::repeat_byte(i))
+                    .build();
+                node.set_rich_account(tx.common_data.initiator_address);
+                tx
+            })
+            .collect::<Vec<_>>();
+
+        let rejected = node
+            .seal_block(txs.clone(), TxExecutionMode::VerifyExecute)
+            .expect("failed sealing block");
+        assert!(rejected.is_empty());
+
+        let inner = node.get_inner();
+        let inner = inner.read().unwrap();
+        let receipts = txs
+            .iter()
+            .map(|tx| inner.tx_results.get(&tx.hash()).unwrap().receipt.clone())
+            .collect::<Vec<_>>();
+        for (tx, receipt) in txs.iter().zip(&receipts) {
+            assert_eq!(tx.hash(), receipt.transaction_hash);
+        }
+        assert_ne!(receipts[0].block_number, receipts[1].block_number);
+        let block = inner.blocks.get(&receipts[1].block_hash.unwrap()).unwrap();
+        assert_eq!(U256::from(gas_used * 3 / 2), block.gas_limit);
+    }
+
+    fn read_archived_value(
+        node: &InMemoryNode<HttpForkSource>,
+        block: u64,
+        key: &StorageKey,
+    ) -> StorageValue {
+        let inner = node.get_inner();
+        let inner = inner.read().unwrap();
+        let diffs = inner
+            .archived_state_at(Some(BlockIdVariant::BlockNumber(
+                zksync_types::api::BlockNumber::Number(U64::from(block)),
+            )))
+            .expect("failed getting archived state");
+        HistoricalStorage::new(&inner.fork_storage, diffs)
+            .read_value_internal(key)
+            .expect("failed reading archived value")
+    }
+
+    #[tokio::test]
+    async fn test_archive_state_keeps_previous_values_of_written_keys_only() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let key = StorageKey::new(AccountTreeId::new(H160::repeat_byte(0x1)), H256::zero());
+
+        for value in 1..=3u8 {
+            node.get_inner()
+                .write()
+                .unwrap()
+                .fork_storage
+                .set_value(key, H256::repeat_byte(value));
+            node.mine_blocks(Some(U64::from(1)), None)
+                .expect("failed mining block");
+        }
+        // written after the last block was archived, so it's tracked by the journal only
+        node.get_inner()
+            .write()
+            .unwrap()
+            .fork_storage
+            .set_value(key, H256::repeat_byte(0x4));
+
+        for block in 0..=3u64 {
+            assert_eq!(
+                H256::repeat_byte(block as u8 + 1),
+                read_archived_value(&node, block, &key),
+                "unexpected value for block {}",
+                block
+            );
+        }
+
+        let inner = node.get_inner();
+        let inner = inner.read().unwrap();
+        let storage_size = inner
+            .fork_storage
+            .inner
+            .read()
+            .unwrap()
+            .raw_storage
+            .state
+            .len();
+        for diff in inner.previous_states.values() {
+            assert!(diff.len() < storage_size);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_archive_state_reads_through_restored_snapshot() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let key = StorageKey::new(AccountTreeId::new(H160::repeat_byte(0x1)), H256::zero());
+        node.get_inner()
+            .write()
+            .unwrap()
+            .fork_storage
+            .set_value(key, H256::repeat_byte(0x1));
+        node.mine_blocks(Some(U64::from(1)), None)
+            .expect("failed mining block");
+
+        let snapshot = node.get_inner().read().unwrap().snapshot().unwrap();
+        node.get_inner()
+            .write()
+            .unwrap()
+            .fork_storage
+            .set_value(key, H256::repeat_byte(0x2));
+        node.mine_blocks(Some(U64::from(1)), None)
+            .expect("failed mining block");
+        node.get_inner()
+            .write()
+            .unwrap()
+            .restore_snapshot(snapshot)
+            .unwrap();
+
+        node.get_inner()
+            .write()
+            .unwrap()
+            .fork_storage
+            .set_value(key, H256::repeat_byte(0x3));
+        assert_eq!(H256::repeat_byte(0x1), read_archived_value(&node, 0, &key));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_shares_unchanged_state() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        testing::apply_tx(&node, H256::repeat_byte(0x1));
+
+        let inner = node.get_inner();
+        let mut writer = inner.write().unwrap();
+        let snapshot = writer.snapshot().expect("failed taking snapshot");
+        {
+            let storage = writer.fork_storage.inner.read().unwrap();
+            assert!(snapshot
+                .raw_storage
+                .state
+                .ptr_eq(&storage.raw_storage.state));
+            assert!(snapshot.tx_results.ptr_eq(&writer.tx_results));
+            assert!(snapshot.blocks.ptr_eq(&writer.blocks));
+        }
+
+        let key = StorageKey::new(AccountTreeId::new(H160::repeat_byte(0x1)), H256::zero());
+        writer.fork_storage.set_value(key, H256::repeat_byte(0x1));
+        let storage = writer.fork_storage.inner.read().unwrap();
+        assert!(!snapshot
+            .raw_storage
+            .state
+            .ptr_eq(&storage.raw_storage.state));
+        assert_eq!(None, snapshot.raw_storage.state.get(&key));
+    }
+
+    #[tokio::test]
+    async fn test_seal_block_excludes_halted_txs() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let tx = testing::TransactionBuilder::new()
+            .set_hash(H256::repeat_byte(0x1))
+            .build();
+        node.set_rich_account(tx.common_data.initiator_address);
+        // the initiator has no funds to pay for the transaction, so the execution halts
+        let unfunded_tx = testing::TransactionBuilder::new()
+            .set_hash(H256::repeat_byte(0x2))
+            .build();
+
+        let rejected = node
+            .seal_block(
+                vec![unfunded_tx.clone(), tx.clone()],
+                TxExecutionMode::VerifyExecute,
+            )
+            .expect("failed sealing block");
+
+        assert_eq!(1, rejected.len());
+        assert_eq!(unfunded_tx.hash(), rejected[0].0);
+        let inner = node.get_inner();
+        let inner = inner.read().unwrap();
+        assert!(!inner.tx_results.contains_key(&unfunded_tx.hash()));
+        let receipt = &inner.tx_results.get(&tx.hash()).unwrap().receipt;
+        assert_eq!(tx.hash(), receipt.transaction_hash);
+        assert_eq!(U64::zero(), receipt.transaction_index);
+    }
+
+    #[tokio::test]
+    async fn test_run_l2_tx_bundle_commits_all_txs_to_a_single_block() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let tx1 = testing::TransactionBuilder::new()
+            .set_hash(H256::repeat_byte(0x1))
+            .build();
+        let tx2 = testing::TransactionBuilder::new()
+            .set_hash(H256::repeat_byte(0x2))
+            .build();
+        node.set_rich_account(tx1.initiator_account());
+        node.set_rich_account(tx2.initiator_account());
+
+        let hashes = node
+            .run_l2_tx_bundle(vec![tx1.clone(), tx2.clone()])
+            .expect("failed running bundle");
+
+        assert_eq!(vec![tx1.hash(), tx2.hash()], hashes);
+        let inner = node.get_inner();
+        let inner = inner.read().unwrap();
+        let first = &inner.tx_results.get(&tx1.hash()).unwrap().receipt;
+        let second = &inner.tx_results.get(&tx2.hash()).unwrap().receipt;
+        assert_eq!(first.block_number, second.block_number);
+        assert_eq!(U64::from(1), second.transaction_index);
+    }
+
+    #[tokio::test]
+    async fn test_run_l2_tx_bundle_includes_no_tx_if_any_fails() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let tx = testing::TransactionBuilder::new()
+            .set_hash(H256::repeat_byte(0x1))
+            .build();
+        node.set_rich_account(tx.initiator_account());
+        // the initiator has no funds to pay for the transaction, so the execution halts
+        let unfunded_tx = testing::TransactionBuilder::new()
+            .set_hash(H256::repeat_byte(0x2))
+            .build();
+        let (block_before, nonce_before) = {
+            let inner = node.get_inner();
+            let inner = inner.read().unwrap();
+            (
+                inner.current_miniblock,
+                inner.account_nonce(tx.initiator_account()).unwrap(),
+            )
+        };
+
+        let result = node.run_l2_tx_bundle(vec![tx.clone(), unfunded_tx]);
+
+        assert!(result.is_err());
+        let inner = node.get_inner();
+        let inner = inner.read().unwrap();
+        assert!(!inner.tx_results.contains_key(&tx.hash()));
+        assert_eq!(block_before, inner.current_miniblock);
+        assert_eq!(
+            nonce_before,
+            inner.account_nonce(tx.initiator_account()).unwrap()
+        );
+    }
+
     #[tokio::test]
     async fn test_create_empty_block_creates_genesis_block_with_hash_and_zero_parent_hash() {
-        let first_block = create_empty_block::<TransactionVariant>(0, 1000, 1, None);
+        let first_block = create_empty_block::<TransactionVariant>(
+            0,
+            1000,
+            1,
+            None,
+            InMemoryNodeConfig::default().block_gas_limit,
+        );
 
         assert_eq!(first_block.hash, compute_hash(0, H256::zero()));
         assert_eq!(first_block.parent_hash, H256::zero());
@@ -1844,8 +3071,20 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_empty_block_creates_block_with_parent_hash_link_to_prev_block() {
-        let first_block = create_empty_block::<TransactionVariant>(0, 1000, 1, None);
-        let second_block = create_empty_block::<TransactionVariant>(1, 1000, 1, None);
+        let first_block = create_empty_block::<TransactionVariant>(
+            0,
+            1000,
+            1,
+            None,
+            InMemoryNodeConfig::default().block_gas_limit,
+        );
+        let second_block = create_empty_block::<TransactionVariant>(
+            1,
+            1000,
+            1,
+            None,
+            InMemoryNodeConfig::default().block_gas_limit,
+        );
 
         assert_eq!(second_block.parent_hash, first_block.hash);
     }
@@ -1857,9 +3096,15 @@ mod tests {
             1000,
             1,
             Some(compute_hash(123, H256::zero())),
+            InMemoryNodeConfig::default().block_gas_limit,
+        );
+        let second_block = create_empty_block::<TransactionVariant>(
+            1,
+            1000,
+            1,
+            Some(first_block.hash),
+            InMemoryNodeConfig::default().block_gas_limit,
         );
-        let second_block =
-            create_empty_block::<TransactionVariant>(1, 1000, 1, Some(first_block.hash));
 
         assert_eq!(first_block.parent_hash, compute_hash(123, H256::zero()));
         assert_eq!(second_block.parent_hash, first_block.hash);
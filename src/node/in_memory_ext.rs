@@ -1,16 +1,20 @@
+use std::{collections::BTreeSet, path::PathBuf};
+
 use anyhow::anyhow;
-use zksync_basic_types::{AccountTreeId, Address, U256, U64};
+use zksync_basic_types::{web3::Bytes, AccountTreeId, Address, H256, U256, U64};
 use zksync_types::{
     get_code_key, get_nonce_key,
+    l2::L2Tx,
     utils::{decompose_full_nonce, nonces_to_full_nonce, storage_key_for_eth_balance},
     StorageKey,
 };
 use zksync_utils::{h256_to_u256, u256_to_h256};
 
 use crate::{
+    config::node::validate_block_gas_limit,
     fork::{ForkDetails, ForkSource},
     namespaces::ResetRequest,
-    node::InMemoryNode,
+    node::{state::SerializableState, InMemoryNode, InMemoryNodeInner},
     utils::{self, bytecode_to_factory_dep},
 };
 
@@ -20,6 +24,38 @@ type Result<T> = anyhow::Result<T>;
 /// and can be used to revert the node to an earlier point in time.
 const MAX_SNAPSHOTS: u8 = 100;
 
+/// The extension of the files named snapshots are written to.
+const SNAPSHOT_FILE_EXTENSION: &str = "json";
+
+/// Makes the next block be mined at exactly `timestamp`, which must be greater than the current timestamp.
+fn set_next_timestamp<S>(writer: &mut InMemoryNodeInner<S>, timestamp: u64) -> Result<()> {
+    if timestamp <= writer.current_timestamp {
+        return Err(anyhow!(
+            "timestamp ({}) must be greater than current timestamp ({})",
+            timestamp,
+            writer.current_timestamp
+        ));
+    }
+    writer.current_timestamp = timestamp - 1;
+    Ok(())
+}
+
+/// Ensures the snapshot `name` is non-empty and can be safely used as a file name.
+fn validate_snapshot_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(anyhow!(
+            "invalid snapshot name '{}', only alphanumeric characters, '-' and '_' are allowed",
+            name
+        ));
+    }
+
+    Ok(())
+}
+
 impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> InMemoryNode<S> {
     /// Increase the current timestamp for the node
     ///
@@ -54,19 +90,8 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> InMemoryNo
         self.get_inner()
             .write()
             .map_err(|err| anyhow!("failed acquiring lock: {:?}", err))
-            .and_then(|mut writer| {
-                let ts = timestamp.as_u64();
-                if ts <= writer.current_timestamp {
-                    Err(anyhow!(
-                        "timestamp ({}) must be greater than current timestamp ({})",
-                        ts,
-                        writer.current_timestamp
-                    ))
-                } else {
-                    writer.current_timestamp = ts - 1;
-                    Ok(timestamp)
-                }
-            })
+            .and_then(|mut writer| set_next_timestamp(&mut writer, timestamp.as_u64()))
+            .map(|_| timestamp)
     }
 
     /// Set the current timestamp for the node.
@@ -91,21 +116,87 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> InMemoryNo
 
     /// Force a single block to be mined.
     ///
-    /// Will mine an empty block (containing zero transactions)
+    /// Will seal all the pending transactions, or mine an empty block (containing zero transactions)
+    /// if there are none.
     ///
     /// # Returns
     /// The string "0x0".
     pub fn mine_block(&self) -> Result<String> {
+        self.get_inner()
+            .write()
+            .map_err(|err| anyhow!("failed acquiring lock: {:?}", err))
+            .and_then(|mut writer| self.mine_block_at(&mut writer, None, vec![]))?;
+        Ok("0x0".to_string())
+    }
+
+    /// Mines `num_blocks` blocks, the first of which seals all the pending transactions followed by `txs`.
+    ///
+    /// # Parameters
+    /// - `timestamp`: The exact timestamp of the first block, must be greater than the current timestamp.
+    ///   The following blocks are mined at the subsequent timestamps
+    /// - `num_blocks`: The number of blocks to mine, defaults to 1. The fictive block closing the batch of
+    ///   the sealed transactions counts toward it, so at least two blocks are mined if there are any
+    /// - `txs`: The transactions to include in the first block, after the pending ones
+    ///
+    /// # Returns
+    /// The string "0x0".
+    pub fn mine_with_options(
+        &self,
+        timestamp: Option<U64>,
+        num_blocks: Option<U64>,
+        txs: Vec<L2Tx>,
+    ) -> Result<String> {
+        let num_blocks = num_blocks.unwrap_or_else(|| U64::from(1));
+        if num_blocks.is_zero() {
+            return Err(anyhow!("Number of blocks must be greater than 0"));
+        }
+
+        for tx in &txs {
+            self.validate_l2_tx(tx).map_err(|err| anyhow!("{}", err))?;
+        }
+
         self.get_inner()
             .write()
             .map_err(|err| anyhow!("failed acquiring lock: {:?}", err))
             .and_then(|mut writer| {
-                utils::mine_empty_blocks(&mut writer, 1, 1000)?;
-                tracing::info!("👷 Mined block #{}", writer.current_miniblock);
+                let last_block = writer.current_miniblock.saturating_add(num_blocks.as_u64());
+                self.mine_block_at(
+                    &mut writer,
+                    timestamp.map(|timestamp| timestamp.as_u64()),
+                    txs,
+                )?;
+                while writer.current_miniblock < last_block {
+                    self.mine_block_at(&mut writer, None, vec![])?;
+                }
                 Ok("0x0".to_string())
             })
     }
 
+    /// Mines a single block that seals all the pending transactions followed by the already validated `txs`,
+    /// or an empty block if there are none. If `timestamp` is given, the block is mined at exactly that timestamp.
+    fn mine_block_at(
+        &self,
+        writer: &mut InMemoryNodeInner<S>,
+        timestamp: Option<u64>,
+        txs: Vec<L2Tx>,
+    ) -> Result<()> {
+        if let Some(timestamp) = timestamp {
+            set_next_timestamp(writer, timestamp)?;
+        }
+
+        if self
+            .mine_pending_txs_locked(writer, txs)
+            .map_err(|err| anyhow!("{}", err))?
+            > 0
+        {
+            return Ok(());
+        }
+
+        utils::mine_empty_blocks(writer, 1, 1000)?;
+        tracing::info!("👷 Mined block #{}", writer.current_miniblock);
+        Ok(())
+    }
+
     /// Snapshot the state of the blockchain at the current block. Takes no parameters. Returns the id of the snapshot
     /// that was created. A snapshot can only be reverted once. After a successful evm_revert, the same snapshot id cannot
     /// be used again. Consider creating a new snapshot after each evm_revert if you need to revert to the same
@@ -188,6 +279,169 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> InMemoryNo
             })
     }
 
+    /// Creates a snapshot of the current state with the given `name`, replacing any previous snapshot with
+    /// the same name. Unlike [InMemoryNode::snapshot], it is not consumed when reverted to. If a snapshot
+    /// directory is configured, the snapshot is also written to disk, so it survives restarts.
+    ///
+    /// # Parameters
+    /// - `name`: The name of the snapshot, consisting of alphanumeric characters, `-` and `_`
+    ///
+    /// # Returns
+    /// `true` on success.
+    pub fn named_snapshot(&self, name: String) -> Result<bool> {
+        validate_snapshot_name(&name)?;
+        let reader = self
+            .get_inner()
+            .read()
+            .map_err(|err| anyhow!("failed acquiring lock: {:?}", err))?;
+        let snapshot = reader.snapshot().map_err(|err| anyhow!("{}", err))?;
+        if let Some(path) = self.snapshot_path(&name) {
+            let state = reader.dump_state().map_err(|err| anyhow!(err))?;
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            // the snapshot is written to a temporary file in the same directory, and then renamed into place,
+            // so the nodes sharing the directory never read a partially written snapshot
+            let tmp_path = path.with_extension(format!(
+                "{}.{}.tmp",
+                SNAPSHOT_FILE_EXTENSION,
+                std::process::id()
+            ));
+            std::fs::write(&tmp_path, serde_json::to_vec(&state)?)
+                .and_then(|_| std::fs::rename(&tmp_path, &path))
+                .map_err(|err| {
+                    let _ = std::fs::remove_file(&tmp_path);
+                    anyhow!("failed writing snapshot to {}: {}", path.display(), err)
+                })?;
+        }
+
+        self.named_snapshots
+            .write()
+            .map_err(|err| anyhow!("failed acquiring write lock for snapshots: {:?}", err))?
+            .insert(name.clone(), snapshot);
+        tracing::info!("Created snapshot '{}'", name);
+        Ok(true)
+    }
+
+    /// Lists the names of the snapshots created with [InMemoryNode::named_snapshot], including the ones
+    /// in the snapshot directory.
+    ///
+    /// # Returns
+    /// The sorted names of the snapshots.
+    pub fn list_snapshots(&self) -> Result<Vec<String>> {
+        let mut names = self
+            .named_snapshots
+            .read()
+            .map_err(|err| anyhow!("failed acquiring read lock for snapshots: {:?}", err))?
+            .keys()
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        if let Some(dir) = self.snapshot_dir.as_ref().filter(|dir| dir.is_dir()) {
+            for entry in std::fs::read_dir(dir)? {
+                let path = entry?.path();
+                if path
+                    .extension()
+                    .map_or(false, |ext| ext == SNAPSHOT_FILE_EXTENSION)
+                {
+                    if let Some(name) = path.file_stem().and_then(|name| name.to_str()) {
+                        names.insert(name.to_string());
+                    }
+                }
+            }
+        }
+
+        Ok(names.into_iter().collect())
+    }
+
+    /// Returns the number of snapshots held in memory, created via `evm_snapshot` or `anvil_snapshot`.
+    pub fn snapshot_count(&self) -> Result<usize> {
+        let snapshots = self
+            .snapshots
+            .read()
+            .map_err(|err| anyhow!("failed acquiring read lock for snapshots: {:?}", err))?
+            .len();
+        let named_snapshots = self
+            .named_snapshots
+            .read()
+            .map_err(|err| anyhow!("failed acquiring read lock for snapshots: {:?}", err))?
+            .len();
+        Ok(snapshots + named_snapshots)
+    }
+
+    /// Deletes the snapshot with the given `name`, both from memory and from the snapshot directory.
+    ///
+    /// # Parameters
+    /// - `name`: The name of the snapshot
+    ///
+    /// # Returns
+    /// `true` if a snapshot was deleted, otherwise `false`.
+    pub fn delete_snapshot(&self, name: String) -> Result<bool> {
+        validate_snapshot_name(&name)?;
+        let mut deleted = self
+            .named_snapshots
+            .write()
+            .map_err(|err| anyhow!("failed acquiring write lock for snapshots: {:?}", err))?
+            .remove(&name)
+            .is_some();
+        if let Some(path) = self.snapshot_path(&name).filter(|path| path.exists()) {
+            std::fs::remove_file(&path)?;
+            deleted = true;
+        }
+
+        if deleted {
+            tracing::info!("Deleted snapshot '{}'", name);
+        }
+        Ok(deleted)
+    }
+
+    /// Reverts the state of the node to the snapshot with the given `name`. The snapshot is kept, so it can be
+    /// reverted to again.
+    ///
+    /// If the snapshot directory holds the snapshot, it is read from there, as another node sharing the directory
+    /// may have overwritten it since it was created.
+    ///
+    /// # Parameters
+    /// - `name`: The name of the snapshot
+    ///
+    /// # Returns
+    /// `true` on success.
+    pub fn revert_to_named_snapshot(&self, name: String) -> Result<bool> {
+        validate_snapshot_name(&name)?;
+        let mut writer = self
+            .get_inner()
+            .write()
+            .map_err(|err| anyhow!("failed acquiring lock: {:?}", err))?;
+        match self.snapshot_path(&name).filter(|path| path.exists()) {
+            Some(path) => {
+                let state: SerializableState = serde_json::from_slice(&std::fs::read(&path)?)
+                    .map_err(|err| anyhow!("failed decoding snapshot '{}': {}", name, err))?;
+                writer.load_state(state).map_err(|err| anyhow!(err))?;
+            }
+            None => {
+                let snapshot = self
+                    .named_snapshots
+                    .read()
+                    .map_err(|err| anyhow!("failed acquiring read lock for snapshots: {:?}", err))?
+                    .get(&name)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("no snapshot exists for the name '{}'", name))?;
+                writer
+                    .restore_snapshot(snapshot)
+                    .map_err(|err| anyhow!("{}", err))?;
+            }
+        }
+
+        tracing::info!("Reverted node to snapshot '{}'", name);
+        Ok(true)
+    }
+
+    /// Returns the path of the file for the snapshot with the given `name`, if a snapshot directory is configured.
+    fn snapshot_path(&self, name: &str) -> Option<PathBuf> {
+        self.snapshot_dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.{}", name, SNAPSHOT_FILE_EXTENSION)))
+    }
+
     pub fn set_balance(&self, address: Address, balance: U256) -> Result<bool> {
         self.get_inner()
             .write()
@@ -250,26 +504,216 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> InMemoryNo
     }
 
     pub fn mine_blocks(&self, num_blocks: Option<U64>, interval: Option<U64>) -> Result<bool> {
+        let num_blocks = num_blocks.unwrap_or_else(|| U64::from(1));
+        if num_blocks.is_zero() {
+            return Err(anyhow!(
+                "Number of blocks must be greater than 0".to_string(),
+            ));
+        }
+
+        let interval_ms = interval
+            .unwrap_or_else(|| U64::from(1))
+            .saturating_mul(1_000.into())
+            .as_u64();
+
+        // every block is mined at an exact timestamp, `interval` apart from the previous one, and the
+        // pending transactions are sealed into the first block. The lock is held until the last block is
+        // mined, so no other request can move the time or mine blocks in between
         self.get_inner()
             .write()
             .map_err(|err| anyhow!("failed acquiring lock: {:?}", err))
             .and_then(|mut writer| {
-                let num_blocks = num_blocks.unwrap_or_else(|| U64::from(1));
-                let interval_ms = interval
-                    .unwrap_or_else(|| U64::from(1))
-                    .saturating_mul(1_000.into());
-                if num_blocks.is_zero() {
-                    return Err(anyhow!(
-                        "Number of blocks must be greater than 0".to_string(),
-                    ));
+                let first_timestamp = writer.current_timestamp.saturating_add(1);
+                for i in 0..num_blocks.as_u64() {
+                    if i > 0 && interval_ms == 0 {
+                        // without an interval, the following blocks share the timestamp of the latest block
+                        writer.current_timestamp = writer.current_timestamp.saturating_sub(1);
+                        self.mine_block_at(&mut writer, None, vec![])?;
+                        continue;
+                    }
+                    let timestamp = first_timestamp.saturating_add(i.saturating_mul(interval_ms));
+                    self.mine_block_at(&mut writer, Some(timestamp), vec![])?;
                 }
-                utils::mine_empty_blocks(&mut writer, num_blocks.as_u64(), interval_ms.as_u64())?;
                 tracing::info!("👷 Mined {} blocks", num_blocks);
+                Ok(true)
+            })
+    }
+
+    /// Discards the latest local blocks, along with their transactions and state changes.
+    ///
+    /// # Parameters
+    /// - `depth`: The number of blocks to discard, defaults to `1`
+    ///
+    /// # Returns
+    /// `true` on success.
+    pub fn rollback(&self, depth: Option<U64>) -> Result<bool> {
+        let depth = depth.unwrap_or_else(U64::one).as_u64();
+        self.get_inner()
+            .write()
+            .map_err(|err| anyhow!("failed acquiring lock: {:?}", err))
+            .and_then(|mut writer| {
+                let block_number =
+                    writer.current_miniblock.checked_sub(depth).ok_or_else(|| {
+                        anyhow!(
+                            "cannot roll back {} blocks, the current block is #{}",
+                            depth,
+                            writer.current_miniblock
+                        )
+                    })?;
+                writer
+                    .rollback_to(block_number)
+                    .map_err(|err| anyhow!("{}", err))?;
+                tracing::info!("👷 Rolled back {} blocks to block #{}", depth, block_number);
+                Ok(true)
+            })
+    }
 
+    /// Rolls the chain back to the given local block, discarding all the blocks after it along with their
+    /// transactions and state changes. Log filters receive the discarded logs with `removed` set to `true`.
+    ///
+    /// # Parameters
+    /// - `block_number`: The block to revert to, its state must still be archived
+    ///
+    /// # Returns
+    /// `true` on success.
+    pub fn revert_to_block(&self, block_number: U64) -> Result<bool> {
+        self.get_inner()
+            .write()
+            .map_err(|err| anyhow!("failed acquiring lock: {:?}", err))
+            .and_then(|mut writer| {
+                writer
+                    .rollback_to(block_number.as_u64())
+                    .map_err(|err| anyhow!("{}", err))?;
+                tracing::info!("👷 Rolled back to block #{}", block_number);
                 Ok(true)
             })
     }
 
+    /// Returns whether automatic mining of submitted transactions is enabled.
+    ///
+    /// # Returns
+    /// `true` if automine is enabled, `false` otherwise.
+    pub fn get_automine(&self) -> Result<bool> {
+        self.get_inner()
+            .read()
+            .map_err(|err| anyhow!("failed acquiring lock: {:?}", err))
+            .map(|reader| reader.automine)
+    }
+
+    /// Enables or disables automatic mining of submitted transactions.
+    ///
+    /// Disabling automine keeps submitted transactions in the pending pool until a block is mined
+    /// explicitly. Enabling it again seals all the pending transactions right away.
+    ///
+    /// # Parameters
+    /// - `enabled`: Whether automine should be enabled
+    ///
+    /// # Returns
+    /// `true` on success.
+    pub fn set_automine(&self, enabled: bool) -> Result<bool> {
+        self.get_inner()
+            .write()
+            .map_err(|err| anyhow!("failed acquiring lock: {:?}", err))
+            .map(|mut writer| {
+                writer.automine = enabled;
+                tracing::info!(
+                    "👷 Automine {}",
+                    if enabled { "enabled" } else { "disabled" }
+                );
+            })?;
+
+        if enabled {
+            self.mine_pending_txs().map_err(|err| anyhow!("{}", err))?;
+        }
+
+        Ok(true)
+    }
+
+    /// Enables or disables interval mining. While enabled, a new block is mined every `interval_ms`
+    /// milliseconds, sealing all the pending transactions, and automine is disabled. Disabling interval
+    /// mining enables automine again, which mines all the pending transactions right away.
+    ///
+    /// # Parameters
+    /// - `interval_ms`: The block time in milliseconds, `0` disables interval mining
+    ///
+    /// # Returns
+    /// `true` on success.
+    pub fn set_interval_mining(&self, interval_ms: u64) -> Result<bool> {
+        let automine = interval_ms == 0;
+        self.get_inner()
+            .write()
+            .map_err(|err| anyhow!("failed acquiring lock: {:?}", err))
+            .map(|mut writer| {
+                writer.automine = automine;
+                if automine {
+                    writer.config.block_time = None;
+                    tracing::info!("👷 Interval mining disabled");
+                } else {
+                    writer.config.block_time = Some(interval_ms);
+                    tracing::info!("👷 Interval mining enabled, block time: {}ms", interval_ms);
+                }
+            })?;
+
+        if automine {
+            self.mine_pending_txs().map_err(|err| anyhow!("{}", err))?;
+        }
+
+        Ok(true)
+    }
+
+    /// Sets the maximum gas used by all the transactions in a block.
+    ///
+    /// # Parameters
+    /// - `gas_limit`: The new block gas limit, must be greater than `0`
+    ///
+    /// # Returns
+    /// `true` on success.
+    pub fn set_block_gas_limit(&self, gas_limit: U64) -> Result<bool> {
+        validate_block_gas_limit(gas_limit.as_u64()).map_err(|err| anyhow!(err))?;
+        self.get_inner()
+            .write()
+            .map_err(|err| anyhow!("failed acquiring lock: {:?}", err))
+            .map(|mut writer| {
+                writer.config.block_gas_limit = gas_limit.as_u64();
+                tracing::info!("👷 Block gas limit set to {}", gas_limit);
+                true
+            })
+    }
+
+    /// Removes a pending or queued transaction from the pool, so it will not be included in a future block.
+    ///
+    /// # Parameters
+    /// - `hash`: The hash of the pending or queued transaction to remove
+    ///
+    /// # Returns
+    /// The hash of the removed transaction, or `None` if no such transaction was in the pool.
+    pub fn drop_transaction(&self, hash: H256) -> Result<Option<H256>> {
+        self.get_inner()
+            .write()
+            .map_err(|err| anyhow!("failed acquiring lock: {:?}", err))
+            .map(|mut writer| {
+                writer.pool.remove(&hash).map(|tx| {
+                    writer.filters.notify_dropped_transaction(tx.hash());
+                    tracing::info!("👷 Dropped transaction {:?}", hash);
+                    tx.hash()
+                })
+            })
+    }
+
+    /// Removes all the pending and queued transactions from the pool.
+    pub fn drop_all_transactions(&self) -> Result<()> {
+        self.get_inner()
+            .write()
+            .map_err(|err| anyhow!("failed acquiring lock: {:?}", err))
+            .map(|mut writer| {
+                let dropped = writer.pool.clear();
+                for tx in &dropped {
+                    writer.filters.notify_dropped_transaction(tx.hash());
+                }
+                tracing::info!("👷 Dropped {} transactions", dropped.len());
+            })
+    }
+
     pub fn reset_network(&self, reset_spec: Option<ResetRequest>) -> Result<bool> {
         let (opt_url, block_number) = if let Some(spec) = reset_spec {
             if let Some(to) = spec.to {
@@ -278,11 +722,25 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> InMemoryNo
                         "Only one of 'to' and 'forking' attributes can be specified"
                     ));
                 }
+                let is_local_block = self
+                    .get_inner()
+                    .read()
+                    .map_err(|err| anyhow!("failed acquiring lock: {:?}", err))?
+                    .block_hashes
+                    .contains_key(&to.as_u64());
+                if is_local_block {
+                    self.reset_to_block(to.as_u64())
+                        .map_err(|err| anyhow!(err))?;
+                    tracing::info!("👷 Network reset to block #{}", to);
+                    return Ok(true);
+                }
                 let url = match self.get_fork_url() {
                     Ok(url) => url,
-                    Err(error) => {
-                        tracing::error!("For returning to past local state, mark it with `evm_snapshot`, then revert to it with `evm_revert`.");
-                        return Err(anyhow!(error.to_string()));
+                    Err(_) => {
+                        return Err(anyhow!(
+                            "block #{} was not produced by the node, and it is not forked",
+                            to
+                        ));
                     }
                 };
                 (Some(url), Some(to.as_u64()))
@@ -317,6 +775,39 @@ impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> InMemoryNo
         }
     }
 
+    /// Serializes the state of the node, so it can be restored later with [InMemoryNode::load_state].
+    ///
+    /// # Returns
+    /// The JSON encoded state, including the storage, blocks and transactions.
+    pub fn dump_state(&self) -> Result<Bytes> {
+        let state = self
+            .get_inner()
+            .read()
+            .map_err(|err| anyhow!("failed acquiring lock: {:?}", err))?
+            .dump_state()
+            .map_err(|err| anyhow!(err))?;
+        Ok(Bytes(serde_json::to_vec(&state)?))
+    }
+
+    /// Replaces the state of the node with one previously returned by [InMemoryNode::dump_state].
+    ///
+    /// # Parameters
+    /// - `buf`: The JSON encoded state
+    ///
+    /// # Returns
+    /// `true` on success.
+    pub fn load_state(&self, buf: Bytes) -> Result<bool> {
+        let state: SerializableState = serde_json::from_slice(&buf.0)
+            .map_err(|err| anyhow!("failed decoding state: {}", err))?;
+        self.get_inner()
+            .write()
+            .map_err(|err| anyhow!("failed acquiring lock: {:?}", err))?
+            .load_state(state)
+            .map_err(|err| anyhow!(err))?;
+        tracing::info!("👷 Loaded state");
+        Ok(true)
+    }
+
     pub fn impersonate_account(&self, address: Address) -> Result<bool> {
         self.get_inner()
             .write()
@@ -395,13 +886,15 @@ mod tests {
     use super::*;
     use crate::fork::ForkStorage;
     use crate::namespaces::EthNamespaceT;
-    use crate::node::{InMemoryNodeInner, Snapshot};
+    use crate::node::{InMemoryNodeInner, Snapshot, TransactionResult};
     use crate::{http_fork_source::HttpForkSource, node::InMemoryNode};
+    use multivm::interface::{ExecutionResult, TxExecutionMode, VmRevertReason};
     use std::str::FromStr;
     use std::sync::{Arc, RwLock};
     use zksync_basic_types::{Nonce, H256};
     use zksync_state::ReadStorage;
     use zksync_types::{api::BlockNumber, fee::Fee, l2::L2Tx, PackedEthSignature};
+    use zksync_web3_decl::types::{Filter, FilterChanges};
 
     #[tokio::test]
     async fn test_set_balance() {
@@ -505,6 +998,111 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_mine_blocks_without_interval_share_timestamp() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let start_block = node
+            .get_block_by_number(BlockNumber::Latest, false)
+            .await
+            .unwrap()
+            .expect("block exists");
+
+        node.mine_blocks(Some(U64::from(3)), Some(U64::zero()))
+            .expect("mine blocks");
+
+        for i in 1..=3 {
+            let block = node
+                .get_block_by_number(BlockNumber::Number(start_block.number + i), false)
+                .await
+                .unwrap()
+                .expect("block exists");
+            assert_eq!(start_block.timestamp + 1, block.timestamp);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mine_blocks_assigns_exact_timestamps_after_pending_txs() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        node.set_automine(false).expect("set_automine");
+        let tx = crate::testing::TransactionBuilder::new().build();
+        node.set_rich_account(tx.initiator_account());
+        node.submit_l2_tx(tx.clone()).expect("failed submitting tx");
+        let first_timestamp = node.get_inner().read().unwrap().current_timestamp + 1;
+
+        node.mine_blocks(Some(U64::from(3)), Some(U64::from(2)))
+            .expect("mine blocks");
+
+        let receipt = node
+            .get_transaction_receipt(tx.hash())
+            .await
+            .expect("failed fetching receipt")
+            .expect("receipt exists");
+        let tx_block = node
+            .get_block_by_number(BlockNumber::Number(receipt.block_number), false)
+            .await
+            .unwrap()
+            .expect("block exists");
+        assert_eq!(U256::from(first_timestamp), tx_block.timestamp);
+        let latest_block = node
+            .get_block_by_number(BlockNumber::Latest, false)
+            .await
+            .unwrap()
+            .expect("block exists");
+        assert_eq!(
+            U256::from(first_timestamp + 2 * 2_000),
+            latest_block.timestamp
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mine_with_options_includes_txs_at_exact_timestamp() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let tx = crate::testing::TransactionBuilder::new().build();
+        node.set_rich_account(tx.initiator_account());
+        let timestamp = node.get_inner().read().unwrap().current_timestamp + 100;
+        let start_block = node
+            .get_block_number()
+            .await
+            .expect("failed fetching block number");
+
+        node.mine_with_options(
+            Some(U64::from(timestamp)),
+            Some(U64::from(2)),
+            vec![tx.clone()],
+        )
+        .expect("failed mining");
+
+        let receipt = node
+            .get_transaction_receipt(tx.hash())
+            .await
+            .expect("failed fetching receipt")
+            .expect("receipt exists");
+        assert_eq!(start_block + 1, receipt.block_number);
+        let tx_block = node
+            .get_block_by_number(BlockNumber::Number(receipt.block_number), false)
+            .await
+            .unwrap()
+            .expect("block exists");
+        assert_eq!(U256::from(timestamp), tx_block.timestamp);
+        // the fictive block closing the batch of the transaction is the second block
+        assert_eq!(
+            start_block + 2,
+            node.get_block_number()
+                .await
+                .expect("failed fetching block number")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mine_with_options_rejects_timestamp_in_the_past() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let timestamp = node.get_inner().read().unwrap().current_timestamp;
+
+        let result = node.mine_with_options(Some(U64::from(timestamp)), None, vec![]);
+
+        assert!(result.is_err());
+    }
+
     #[tokio::test]
     async fn test_reset() {
         let old_snapshots = Arc::new(RwLock::new(vec![Snapshot::default()]));
@@ -519,6 +1117,7 @@ mod tests {
             blocks: Default::default(),
             block_hashes: Default::default(),
             filters: Default::default(),
+            subscriptions: Default::default(),
             fork_storage: ForkStorage::new(None, &old_system_contracts_options),
             config: Default::default(),
             console_log_handler: Default::default(),
@@ -527,11 +1126,15 @@ mod tests {
             rich_accounts: Default::default(),
             previous_states: Default::default(),
             observability: None,
+            pool: Default::default(),
+            automine: true,
         };
 
         let node = InMemoryNode::<HttpForkSource> {
             inner: Arc::new(RwLock::new(old_inner)),
             snapshots: old_snapshots,
+            named_snapshots: Default::default(),
+            snapshot_dir: None,
             system_contracts_options: old_system_contracts_options,
         };
 
@@ -556,6 +1159,59 @@ mod tests {
         assert_ne!(inner.current_miniblock_hash, H256::random());
     }
 
+    #[tokio::test]
+    async fn test_reset_to_local_block_keeps_history_up_to_it() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let kept_tx_hash = H256::repeat_byte(0x1);
+        let discarded_tx_hash = H256::repeat_byte(0x2);
+        let (_, kept_block_number) = crate::testing::apply_tx(&node, kept_tx_hash);
+        // the fictive block of the batch
+        let reset_block_number = kept_block_number + 1;
+        crate::testing::apply_tx(&node, discarded_tx_hash);
+        node.snapshot().expect("failed creating snapshot");
+
+        let reset_result = node
+            .reset_network(Some(ResetRequest {
+                to: Some(reset_block_number),
+                forking: None,
+            }))
+            .expect("failed resetting network");
+        assert!(reset_result);
+
+        assert_eq!(reset_block_number, node.get_block_number().await.unwrap());
+        assert!(node
+            .get_transaction_by_hash(kept_tx_hash)
+            .await
+            .unwrap()
+            .is_some());
+        assert!(node
+            .get_transaction_by_hash(discarded_tx_hash)
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(node.snapshots.read().unwrap().len(), 0);
+
+        // the node keeps producing blocks from the reset one
+        crate::testing::apply_tx(&node, discarded_tx_hash);
+        assert_eq!(
+            reset_block_number + 2,
+            node.get_block_number().await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reset_to_unknown_block_fails_without_fork() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        node.mine_block().expect("mine_block");
+
+        let result = node.reset_network(Some(ResetRequest {
+            to: Some(U64::from(10)),
+            forking: None,
+        }));
+        assert!(result.is_err());
+        assert_eq!(U64::one(), node.get_block_number().await.unwrap());
+    }
+
     #[tokio::test]
     async fn test_impersonate_account() {
         let node = InMemoryNode::<HttpForkSource>::default();
@@ -992,6 +1648,391 @@ mod tests {
         assert_eq!(start_block.timestamp + 2, current_block.timestamp);
     }
 
+    #[tokio::test]
+    async fn test_disabled_automine_keeps_tx_pending_until_mined() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        assert!(node.get_automine().expect("get_automine"));
+        node.set_automine(false).expect("set_automine");
+        assert!(!node.get_automine().expect("get_automine"));
+
+        let start_block = node
+            .get_block_number()
+            .await
+            .expect("failed fetching block number");
+        let tx = crate::testing::TransactionBuilder::new().build();
+        node.set_rich_account(tx.initiator_account());
+        node.submit_l2_tx(tx.clone()).expect("failed submitting tx");
+
+        assert_eq!(
+            start_block,
+            node.get_block_number()
+                .await
+                .expect("failed fetching block number")
+        );
+        let pending = node
+            .get_transaction_by_hash(tx.hash())
+            .await
+            .expect("failed fetching tx")
+            .expect("pending tx exists");
+        assert_eq!(None, pending.block_number);
+
+        node.mine_block().expect("mine_block");
+
+        assert!(node.get_inner().read().unwrap().pool.is_empty());
+        let receipt = node
+            .get_transaction_receipt(tx.hash())
+            .await
+            .expect("failed fetching receipt")
+            .expect("receipt exists");
+        assert_eq!(start_block + 1, receipt.block_number);
+    }
+
+    #[tokio::test]
+    async fn test_enabling_automine_seals_pending_txs() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        node.set_automine(false).expect("set_automine");
+
+        let tx = crate::testing::TransactionBuilder::new().build();
+        node.set_rich_account(tx.initiator_account());
+        node.submit_l2_tx(tx.clone()).expect("failed submitting tx");
+        assert_eq!(1, node.get_inner().read().unwrap().pool.len());
+
+        node.set_automine(true).expect("set_automine");
+
+        assert!(node.get_inner().read().unwrap().pool.is_empty());
+        assert!(node
+            .get_transaction_receipt(tx.hash())
+            .await
+            .expect("failed fetching receipt")
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_drop_transaction_removes_pending_tx() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        node.set_automine(false).expect("set_automine");
+
+        let tx = crate::testing::TransactionBuilder::new().build();
+        node.set_rich_account(tx.initiator_account());
+        node.submit_l2_tx(tx.clone()).expect("failed submitting tx");
+
+        assert_eq!(
+            Some(tx.hash()),
+            node.drop_transaction(tx.hash()).expect("drop_transaction")
+        );
+        assert_eq!(
+            None,
+            node.drop_transaction(tx.hash()).expect("drop_transaction")
+        );
+        assert!(node
+            .get_transaction_by_hash(tx.hash())
+            .await
+            .expect("failed fetching tx")
+            .is_none());
+
+        node.mine_block().expect("mine_block");
+        assert!(node
+            .get_transaction_receipt(tx.hash())
+            .await
+            .expect("failed fetching receipt")
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_drop_all_transactions_clears_pool() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        node.set_automine(false).expect("set_automine");
+
+        for hash in [H256::repeat_byte(0x1), H256::repeat_byte(0x2)] {
+            let tx = crate::testing::TransactionBuilder::new()
+                .set_hash(hash)
+                .build();
+            node.set_rich_account(tx.initiator_account());
+            node.submit_l2_tx(tx).expect("failed submitting tx");
+        }
+        assert_eq!(2, node.get_inner().read().unwrap().pool.len());
+
+        node.drop_all_transactions().expect("drop_all_transactions");
+
+        assert!(node.get_inner().read().unwrap().pool.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_load_state_restores_dumped_state() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let tx = crate::testing::TransactionBuilder::new().build();
+        node.set_rich_account(tx.initiator_account());
+        node.submit_l2_tx(tx.clone()).expect("failed submitting tx");
+        let address = Address::repeat_byte(0x1);
+        node.set_balance(address, U256::from(1337))
+            .expect("failed setting balance");
+
+        let state = node.dump_state().expect("failed dumping state");
+        let loaded_node = InMemoryNode::<HttpForkSource>::default();
+        loaded_node.load_state(state).expect("failed loading state");
+
+        assert_eq!(
+            node.get_block_number().await.unwrap(),
+            loaded_node.get_block_number().await.unwrap()
+        );
+        let receipt = loaded_node
+            .get_transaction_receipt(tx.hash())
+            .await
+            .unwrap()
+            .expect("receipt exists");
+        assert_eq!(
+            node.get_transaction_receipt(tx.hash())
+                .await
+                .unwrap()
+                .unwrap()
+                .block_hash,
+            receipt.block_hash
+        );
+        assert_eq!(
+            U256::from(1337),
+            loaded_node.get_balance(address, None).await.unwrap()
+        );
+        let inner = loaded_node.get_inner();
+        let inner = inner.read().unwrap();
+        assert_eq!(
+            node.get_inner().read().unwrap().current_timestamp,
+            inner.current_timestamp
+        );
+        assert_eq!(inner.blocks.len(), inner.block_hashes.len());
+    }
+
+    #[tokio::test]
+    async fn test_load_state_restores_reverted_txs() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let tx_hash = H256::repeat_byte(0x1);
+        {
+            let inner = node.get_inner();
+            let mut writer = inner.write().unwrap();
+            let mut info = crate::testing::default_tx_execution_info();
+            info.result.result = ExecutionResult::Revert {
+                output: VmRevertReason::General {
+                    msg: "reverted".to_string(),
+                    data: vec![0x1, 0x2],
+                },
+            };
+            writer.tx_results.insert(
+                tx_hash,
+                TransactionResult {
+                    info,
+                    receipt: Default::default(),
+                    debug: crate::testing::default_tx_debug_info(),
+                },
+            );
+        }
+
+        let state = node.dump_state().expect("failed dumping state");
+        let loaded_node = InMemoryNode::<HttpForkSource>::default();
+        loaded_node.load_state(state).expect("failed loading state");
+
+        let inner = loaded_node.get_inner();
+        let inner = inner.read().unwrap();
+        match &inner.tx_results.get(&tx_hash).unwrap().info.result.result {
+            ExecutionResult::Revert { output } => {
+                assert_eq!("reverted", output.to_user_friendly_string());
+                assert_eq!(vec![0x1, 0x2], output.encoded_data());
+            }
+            result => panic!("expected a reverted result, got {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_load_state_drops_pending_txs_and_resets_filters() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let state = node.dump_state().expect("failed dumping state");
+        node.set_automine(false).expect("set_automine");
+        let tx = crate::testing::TransactionBuilder::new().build();
+        node.set_rich_account(tx.initiator_account());
+        node.submit_l2_tx(tx).expect("failed submitting tx");
+        let filter_id = node
+            .get_inner()
+            .write()
+            .unwrap()
+            .filters
+            .add_pending_transaction_filter()
+            .expect("failed adding filter");
+
+        node.load_state(state).expect("failed loading state");
+
+        let inner = node.get_inner();
+        let inner = inner.read().unwrap();
+        assert!(inner.pool.is_empty());
+        assert!(inner.filters.get_filter(filter_id).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_load_state_rejects_unsupported_version() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let mut state: serde_json::Value =
+            serde_json::from_slice(&node.dump_state().expect("failed dumping state").0).unwrap();
+        state["version"] = serde_json::Value::from(u32::MAX);
+
+        let result = node.load_state(Bytes(serde_json::to_vec(&state).unwrap()));
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_queued_txs_are_mined_once_nonce_gap_is_filled() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let private_key = zksync_types::K256PrivateKey::from_bytes(H256::random()).unwrap();
+        let txs = (0..3)
+            .map(|nonce| {
+                crate::testing::TransactionBuilder::new()
+                    .set_from_account_private_key(private_key.clone())
+                    .set_nonce(Nonce(nonce))
+                    .set_hash(H256::repeat_byte(nonce as u8 + 1))
+                    .build()
+            })
+            .collect::<Vec<_>>();
+        node.set_rich_account(txs[0].initiator_account());
+
+        node.submit_l2_tx(txs[2].clone())
+            .expect("failed submitting tx");
+        node.submit_l2_tx(txs[1].clone())
+            .expect("failed submitting tx");
+        assert_eq!(2, node.get_inner().read().unwrap().pool.queued_len());
+        assert!(node
+            .get_transaction_receipt(txs[1].hash())
+            .await
+            .expect("failed fetching receipt")
+            .is_none());
+
+        node.submit_l2_tx(txs[0].clone())
+            .expect("failed submitting tx");
+
+        assert_eq!(0, node.get_inner().read().unwrap().pool.queued_len());
+        for tx in &txs {
+            assert!(node
+                .get_transaction_receipt(tx.hash())
+                .await
+                .expect("failed fetching receipt")
+                .is_some());
+        }
+        assert_eq!(
+            Nonce(3),
+            node.get_inner()
+                .read()
+                .unwrap()
+                .account_nonce(txs[0].initiator_account())
+                .expect("failed reading nonce")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_queued_txs_are_promoted_to_pending_pool_once_nonce_gap_is_filled() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        node.set_automine(false).expect("set_automine");
+        let private_key = zksync_types::K256PrivateKey::from_bytes(H256::random()).unwrap();
+        let txs = (0..2)
+            .map(|nonce| {
+                crate::testing::TransactionBuilder::new()
+                    .set_from_account_private_key(private_key.clone())
+                    .set_nonce(Nonce(nonce))
+                    .set_hash(H256::repeat_byte(nonce as u8 + 1))
+                    .build()
+            })
+            .collect::<Vec<_>>();
+        node.set_rich_account(txs[0].initiator_account());
+
+        node.submit_l2_tx(txs[1].clone())
+            .expect("failed submitting tx");
+        node.mine_block().expect("mine_block");
+        assert_eq!(1, node.get_inner().read().unwrap().pool.queued_len());
+
+        node.submit_l2_tx(txs[0].clone())
+            .expect("failed submitting tx");
+        assert_eq!(2, node.get_inner().read().unwrap().pool.len());
+        node.mine_block().expect("mine_block");
+
+        let receipts = [
+            node.get_transaction_receipt(txs[0].hash()).await,
+            node.get_transaction_receipt(txs[1].hash()).await,
+        ]
+        .map(|receipt| {
+            receipt
+                .expect("failed fetching receipt")
+                .expect("receipt exists")
+        });
+        assert_eq!(receipts[0].block_number, receipts[1].block_number);
+    }
+
+    #[tokio::test]
+    async fn test_queued_txs_are_promoted_once_nonce_gap_is_filled_by_sealed_block() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        node.set_automine(false).expect("set_automine");
+        let private_key = zksync_types::K256PrivateKey::from_bytes(H256::random()).unwrap();
+        let txs = (0..2)
+            .map(|nonce| {
+                crate::testing::TransactionBuilder::new()
+                    .set_from_account_private_key(private_key.clone())
+                    .set_nonce(Nonce(nonce))
+                    .set_hash(H256::repeat_byte(nonce as u8 + 1))
+                    .build()
+            })
+            .collect::<Vec<_>>();
+        node.set_rich_account(txs[0].initiator_account());
+
+        node.submit_l2_tx(txs[1].clone())
+            .expect("failed submitting tx");
+        node.run_l2_tx(txs[0].clone(), TxExecutionMode::VerifyExecute)
+            .expect("failed running tx");
+        {
+            let inner = node.get_inner();
+            let inner = inner.read().unwrap();
+            assert_eq!(0, inner.pool.queued_len());
+            assert_eq!(1, inner.pool.len());
+        }
+
+        node.mine_block().expect("mine_block");
+
+        assert!(node
+            .get_transaction_receipt(txs[1].hash())
+            .await
+            .expect("failed fetching receipt")
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_set_interval_mining() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        assert_eq!(None, node.get_inner().read().unwrap().config.block_time);
+
+        node.set_interval_mining(1500)
+            .expect("failed enabling interval mining");
+        assert_eq!(
+            Some(1500),
+            node.get_inner().read().unwrap().config.block_time
+        );
+        assert!(!node.get_inner().read().unwrap().automine);
+
+        node.set_interval_mining(0)
+            .expect("failed disabling interval mining");
+        assert_eq!(None, node.get_inner().read().unwrap().config.block_time);
+        assert!(node.get_inner().read().unwrap().automine);
+    }
+
+    #[tokio::test]
+    async fn test_set_block_gas_limit_is_reported_by_new_blocks() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        assert!(node.set_block_gas_limit(U64::zero()).is_err());
+
+        node.set_block_gas_limit(U64::from(30_000_000))
+            .expect("failed setting block gas limit");
+        node.mine_block().expect("failed mining block");
+
+        let block = node
+            .get_block_by_number(BlockNumber::Latest, false)
+            .await
+            .unwrap()
+            .expect("block exists");
+        assert_eq!(U256::from(30_000_000), block.gas_limit);
+    }
+
     #[tokio::test]
     async fn test_evm_snapshot_creates_incrementing_ids() {
         let node = InMemoryNode::<HttpForkSource>::default();
@@ -1048,6 +2089,99 @@ mod tests {
         assert_eq!(1, node.snapshots.read().unwrap().len());
     }
 
+    #[tokio::test]
+    async fn test_named_snapshot_can_be_reverted_to_repeatedly() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let address = Address::repeat_byte(0x1);
+        node.set_balance(address, U256::from(1))
+            .expect("failed setting balance");
+        node.named_snapshot("after-deploy".to_string())
+            .expect("failed creating snapshot");
+
+        for balance in [2, 3] {
+            node.set_balance(address, U256::from(balance))
+                .expect("failed setting balance");
+            node.revert_to_named_snapshot("after-deploy".to_string())
+                .expect("failed reverting to snapshot");
+            assert_eq!(
+                U256::from(1),
+                node.get_balance(address, None).await.unwrap()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_named_snapshot_is_shared_through_the_snapshot_dir() {
+        let dir = tempdir::TempDir::new("snapshots").expect("failed creating temporary dir");
+        let node = InMemoryNode::<HttpForkSource>::default()
+            .with_snapshot_dir(Some(dir.path().to_path_buf()));
+        let address = Address::repeat_byte(0x1);
+        node.set_balance(address, U256::from(1337))
+            .expect("failed setting balance");
+        node.named_snapshot("checkpoint".to_string())
+            .expect("failed creating snapshot");
+
+        let other_node = InMemoryNode::<HttpForkSource>::default()
+            .with_snapshot_dir(Some(dir.path().to_path_buf()));
+        assert_eq!(
+            vec!["checkpoint".to_string()],
+            other_node.list_snapshots().unwrap()
+        );
+        other_node
+            .revert_to_named_snapshot("checkpoint".to_string())
+            .expect("failed reverting to snapshot");
+        assert_eq!(
+            U256::from(1337),
+            other_node.get_balance(address, None).await.unwrap()
+        );
+
+        assert!(other_node
+            .delete_snapshot("checkpoint".to_string())
+            .expect("failed deleting snapshot"));
+        assert!(other_node.list_snapshots().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_named_snapshot_prefers_the_copy_in_the_snapshot_dir() {
+        let dir = tempdir::TempDir::new("snapshots").expect("failed creating temporary dir");
+        let node = InMemoryNode::<HttpForkSource>::default()
+            .with_snapshot_dir(Some(dir.path().to_path_buf()));
+        let other_node = InMemoryNode::<HttpForkSource>::default()
+            .with_snapshot_dir(Some(dir.path().to_path_buf()));
+        let address = Address::repeat_byte(0x1);
+        node.set_balance(address, U256::from(1337))
+            .expect("failed setting balance");
+        node.named_snapshot("checkpoint".to_string())
+            .expect("failed creating snapshot");
+        other_node
+            .set_balance(address, U256::from(42))
+            .expect("failed setting balance");
+        other_node
+            .named_snapshot("checkpoint".to_string())
+            .expect("failed creating snapshot");
+
+        node.revert_to_named_snapshot("checkpoint".to_string())
+            .expect("failed reverting to snapshot");
+
+        assert_eq!(
+            U256::from(42),
+            node.get_balance(address, None).await.unwrap()
+        );
+        // the temporary files the snapshots were written to were renamed into place
+        assert_eq!(1, std::fs::read_dir(dir.path()).unwrap().count());
+    }
+
+    #[tokio::test]
+    async fn test_named_snapshot_rejects_invalid_names() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+
+        assert!(node.named_snapshot("".to_string()).is_err());
+        assert!(node.named_snapshot("../escape".to_string()).is_err());
+        assert!(node
+            .revert_to_named_snapshot("missing".to_string())
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_evm_revert_snapshot_fails_for_invalid_snapshot_id() {
         let node = InMemoryNode::<HttpForkSource>::default();
@@ -1055,4 +2189,92 @@ mod tests {
         let result = node.revert_snapshot(U64::from(100));
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_revert_to_block_discards_later_blocks_and_state() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let tx_hash = H256::repeat_byte(0x1);
+        let tx = crate::testing::TransactionBuilder::new()
+            .set_hash(tx_hash)
+            .build();
+        let initiator = tx.common_data.initiator_address;
+        node.set_rich_account(initiator);
+        let balance_before = node.get_balance(initiator, None).await.unwrap();
+        node.apply_txs(vec![tx]).expect("failed applying tx");
+        assert_eq!(
+            U256::one(),
+            node.get_transaction_count(initiator, None).await.unwrap()
+        );
+
+        let result = node
+            .revert_to_block(U64::zero())
+            .expect("failed reverting to block");
+        assert!(result);
+
+        {
+            let inner = node.get_inner();
+            let reader = inner.read().unwrap();
+            assert_eq!(0, reader.current_miniblock);
+            assert!(!reader.tx_results.contains_key(&tx_hash));
+            assert!(!reader.block_hashes.contains_key(&1));
+            assert_eq!(1, reader.blocks.len());
+        }
+        assert_eq!(
+            U256::zero(),
+            node.get_transaction_count(initiator, None).await.unwrap()
+        );
+        assert_eq!(
+            balance_before,
+            node.get_balance(initiator, None).await.unwrap()
+        );
+        assert!(node
+            .get_transaction_by_hash(tx_hash)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rollback_notifies_log_filters_of_removed_logs() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        let filter_id = node.new_filter(Filter::default()).await.unwrap();
+        crate::testing::apply_tx(&node, H256::repeat_byte(0x1));
+        let added_logs = match node.get_filter_changes(filter_id).await.unwrap() {
+            FilterChanges::Logs(logs) => logs,
+            changes => panic!("unexpected filter changes: {:?}", changes),
+        };
+        assert!(!added_logs.is_empty());
+
+        // discards the tx block and its fictive block
+        node.rollback(Some(U64::from(2)))
+            .expect("failed rolling back");
+
+        match node.get_filter_changes(filter_id).await.unwrap() {
+            FilterChanges::Logs(logs) => {
+                assert_eq!(added_logs.len(), logs.len());
+                assert!(logs.iter().all(|log| log.removed == Some(true)));
+                let mut removed_indices = logs.iter().map(|log| log.log_index).collect::<Vec<_>>();
+                removed_indices.reverse();
+                assert_eq!(
+                    added_logs
+                        .iter()
+                        .map(|log| log.log_index)
+                        .collect::<Vec<_>>(),
+                    removed_indices
+                );
+            }
+            changes => panic!("unexpected filter changes: {:?}", changes),
+        }
+        assert_eq!(U64::zero(), node.get_block_number().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_rollback_fails_beyond_the_first_block() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        node.mine_block().expect("mine_block");
+
+        assert!(node.rollback(Some(U64::from(2))).is_err());
+        assert!(node.revert_to_block(U64::from(1)).is_err());
+        assert_eq!(U64::one(), node.get_block_number().await.unwrap());
+    }
 }
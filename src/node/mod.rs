@@ -1,17 +1,24 @@
 //! In-memory node, that supports forking other networks.
 
 mod anvil;
+mod batch;
+mod block_producer;
 mod config_api;
 mod debug;
 mod eth;
+mod eth_pubsub;
 mod evm;
 mod fee_model;
 mod hardhat;
 mod in_memory;
 mod in_memory_ext;
 mod net;
+mod pool;
+mod state;
 mod storage_logs;
+mod txpool;
 mod web3;
 mod zks;
 
+pub use block_producer::BlockProducer;
 pub use in_memory::*;
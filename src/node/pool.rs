@@ -0,0 +1,410 @@
+use indexmap::IndexMap;
+use zksync_basic_types::{Address, Nonce, H256, U256};
+use zksync_types::{api, l2::L2Tx};
+
+/// Keeps track of transactions that were submitted to the node, but not yet sealed into a block.
+///
+/// Transactions whose nonce directly follows the sender's account nonce (or its last pending transaction)
+/// are `pending`, and can be sealed into the next block, in the order they were received. Transactions with
+/// a nonce gap are `queued`, and are promoted to pending once the gap is filled.
+/// There is at most one transaction per sender and nonce.
+#[derive(Debug, Default, Clone)]
+pub struct TxPool {
+    pending: IndexMap<(Address, Nonce), L2Tx>,
+    queued: IndexMap<(Address, Nonce), L2Tx>,
+}
+
+/// The outcome of adding a transaction to the [TxPool].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AddedTx {
+    /// Whether the transaction was queued because of a nonce gap.
+    pub queued: bool,
+    /// The hash of the transaction with the same sender and nonce that was replaced, if any.
+    pub replaced: Option<H256>,
+    /// The hashes of the queued transactions that were promoted to pending.
+    pub promoted: Vec<H256>,
+}
+
+impl TxPool {
+    /// Adds a transaction to the pool, given the current nonce of its sender's account.
+    ///
+    /// A transaction with the same sender and nonce is replaced in place if the new transaction pays
+    /// a higher fee, in which case the hash of the replaced transaction is returned.
+    pub fn add(&mut self, tx: L2Tx, account_nonce: Nonce) -> Result<AddedTx, String> {
+        let sender = tx.initiator_account();
+        let nonce = tx.common_data.nonce;
+        if nonce < account_nonce {
+            return Err(format!(
+                "nonce too low: next nonce {}, tx nonce {}",
+                account_nonce, nonce
+            ));
+        }
+
+        let key = (sender, nonce);
+        for (queued, txs) in [(false, &mut self.pending), (true, &mut self.queued)] {
+            if let Some(existing) = txs.get(&key) {
+                if existing.hash() == tx.hash() {
+                    return Err("transaction already known".into());
+                }
+                if !pays_higher_fee(&tx, existing) {
+                    return Err("replacement transaction underpriced".into());
+                }
+                return Ok(AddedTx {
+                    queued,
+                    replaced: txs.insert(key, tx).map(|replaced| replaced.hash()),
+                    promoted: vec![],
+                });
+            }
+        }
+
+        if nonce != self.next_nonce(sender, account_nonce) {
+            self.queued.insert(key, tx);
+            return Ok(AddedTx {
+                queued: true,
+                ..Default::default()
+            });
+        }
+
+        self.pending.insert(key, tx);
+        Ok(AddedTx {
+            promoted: self.promote(sender, account_nonce),
+            ..Default::default()
+        })
+    }
+
+    /// Moves the queued transactions of `sender` that are no longer blocked by a nonce gap to pending.
+    ///
+    /// Returns the hashes of the promoted transactions.
+    pub fn promote(&mut self, sender: Address, account_nonce: Nonce) -> Vec<H256> {
+        let mut promoted = vec![];
+        let mut next_nonce = self.next_nonce(sender, account_nonce);
+        while let Some(tx) = self.queued.shift_remove(&(sender, next_nonce)) {
+            promoted.push(tx.hash());
+            self.pending.insert((sender, next_nonce), tx);
+            next_nonce = Nonce(next_nonce.0 + 1);
+        }
+        promoted
+    }
+
+    /// Returns the pending or queued transaction with the given hash, if any.
+    pub fn get(&self, hash: &H256) -> Option<&L2Tx> {
+        self.pending
+            .values()
+            .chain(self.queued.values())
+            .find(|tx| &tx.hash() == hash)
+    }
+
+    /// Removes the pending or queued transaction with the given hash from the pool, and returns it.
+    ///
+    /// Pending transactions of the same sender with a higher nonce can no longer be executed,
+    /// so they are moved back to the queue.
+    pub fn remove(&mut self, hash: &H256) -> Option<L2Tx> {
+        if let Some(index) = self.queued.values().position(|tx| &tx.hash() == hash) {
+            return self.queued.shift_remove_index(index).map(|(_, tx)| tx);
+        }
+
+        let index = self.pending.values().position(|tx| &tx.hash() == hash)?;
+        let ((sender, nonce), tx) = self.pending.shift_remove_index(index)?;
+        let demoted = self
+            .pending
+            .keys()
+            .filter(|(address, other)| *address == sender && *other > nonce)
+            .copied()
+            .collect::<Vec<_>>();
+        for key in demoted {
+            if let Some(tx) = self.pending.shift_remove(&key) {
+                self.queued.insert(key, tx);
+            }
+        }
+        Some(tx)
+    }
+
+    /// Returns an iterator over the pending transactions, in submission order.
+    pub fn pending(&self) -> impl Iterator<Item = &L2Tx> {
+        self.pending.values()
+    }
+
+    /// Returns an iterator over the queued transactions, in submission order.
+    pub fn queued(&self) -> impl Iterator<Item = &L2Tx> {
+        self.queued.values()
+    }
+
+    /// Returns an iterator over the pending transactions sent by the given address.
+    pub fn txs_from(&self, address: Address) -> impl Iterator<Item = &L2Tx> {
+        self.pending
+            .values()
+            .filter(move |tx| tx.initiator_account() == address)
+    }
+
+    /// Returns the number of pending transactions.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns `true` if there are no pending transactions.
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Returns the number of queued transactions.
+    pub fn queued_len(&self) -> usize {
+        self.queued.len()
+    }
+
+    /// Removes all the pending transactions from the pool and returns them in submission order.
+    /// Queued transactions are kept.
+    pub fn take_all(&mut self) -> Vec<L2Tx> {
+        self.pending.drain(..).map(|(_, tx)| tx).collect()
+    }
+
+    /// Removes all the pending and queued transactions from the pool and returns them.
+    pub fn clear(&mut self) -> Vec<L2Tx> {
+        self.pending
+            .drain(..)
+            .chain(self.queued.drain(..))
+            .map(|(_, tx)| tx)
+            .collect()
+    }
+
+    /// Returns the nonce that directly follows the pending transactions of `sender`.
+    pub fn next_nonce(&self, sender: Address, account_nonce: Nonce) -> Nonce {
+        let mut next_nonce = account_nonce;
+        while self.pending.contains_key(&(sender, next_nonce)) {
+            next_nonce = Nonce(next_nonce.0 + 1);
+        }
+        next_nonce
+    }
+}
+
+/// Converts a transaction from the pool to its API representation. It is not part of any block yet.
+pub fn to_api_transaction(tx: &L2Tx) -> api::Transaction {
+    let chain_id = tx.common_data.extract_chain_id().unwrap_or_default();
+    api::Transaction {
+        hash: tx.hash(),
+        nonce: U256::from(tx.common_data.nonce.0),
+        block_hash: None,
+        block_number: None,
+        transaction_index: None,
+        from: Some(tx.initiator_account()),
+        to: Some(tx.recipient_account()),
+        value: tx.execute.value,
+        gas_price: Some(tx.common_data.fee.max_fee_per_gas),
+        gas: tx.common_data.fee.gas_limit,
+        input: tx.execute.calldata.clone().into(),
+        v: Some(chain_id.into()),
+        r: Some(U256::zero()),
+        s: Some(U256::zero()),
+        raw: None,
+        transaction_type: Some((tx.common_data.transaction_type as u32).into()),
+        access_list: None,
+        max_fee_per_gas: Some(tx.common_data.fee.max_fee_per_gas),
+        max_priority_fee_per_gas: Some(tx.common_data.fee.max_priority_fee_per_gas),
+        chain_id: U256::from(chain_id),
+        l1_batch_number: None,
+        l1_batch_tx_index: None,
+    }
+}
+
+/// Returns `true` if `tx` pays enough to replace the `existing` transaction with the same nonce.
+fn pays_higher_fee(tx: &L2Tx, existing: &L2Tx) -> bool {
+    tx.common_data.fee.max_fee_per_gas > existing.common_data.fee.max_fee_per_gas
+        && tx.common_data.fee.max_priority_fee_per_gas
+            >= existing.common_data.fee.max_priority_fee_per_gas
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_basic_types::H256;
+    use zksync_types::K256PrivateKey;
+
+    use super::*;
+    use crate::testing;
+
+    fn build_tx(private_key: &K256PrivateKey, nonce: u32, hash: u8) -> L2Tx {
+        testing::TransactionBuilder::new()
+            .set_from_account_private_key(private_key.clone())
+            .set_nonce(Nonce(nonce))
+            .set_hash(H256::repeat_byte(hash))
+            .build()
+    }
+
+    #[test]
+    fn test_add_keeps_submission_order() {
+        let mut pool = TxPool::default();
+        let tx1 = testing::TransactionBuilder::new()
+            .set_hash(H256::repeat_byte(0x2))
+            .build();
+        let tx2 = testing::TransactionBuilder::new()
+            .set_hash(H256::repeat_byte(0x1))
+            .build();
+
+        pool.add(tx1.clone(), Nonce(0)).expect("failed adding tx");
+        pool.add(tx2.clone(), Nonce(0)).expect("failed adding tx");
+
+        assert_eq!(2, pool.len());
+        assert!(pool.get(&tx1.hash()).is_some());
+        assert_eq!(
+            vec![tx1.hash(), tx2.hash()],
+            pool.take_all()
+                .iter()
+                .map(|tx| tx.hash())
+                .collect::<Vec<_>>()
+        );
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    fn test_txs_from_filters_by_initiator() {
+        let mut pool = TxPool::default();
+        let tx1 = testing::TransactionBuilder::new()
+            .set_hash(H256::repeat_byte(0x1))
+            .build();
+        let tx2 = testing::TransactionBuilder::new()
+            .set_hash(H256::repeat_byte(0x2))
+            .build();
+
+        pool.add(tx1.clone(), Nonce(0)).expect("failed adding tx");
+        pool.add(tx2, Nonce(0)).expect("failed adding tx");
+
+        let from_first = pool.txs_from(tx1.initiator_account()).collect::<Vec<_>>();
+        assert_eq!(1, from_first.len());
+        assert_eq!(tx1.hash(), from_first[0].hash());
+    }
+
+    #[test]
+    fn test_add_replaces_same_nonce_tx_paying_higher_fee() {
+        let mut pool = TxPool::default();
+        let tx1 = testing::TransactionBuilder::new()
+            .set_hash(H256::repeat_byte(0x1))
+            .build();
+        let other = testing::TransactionBuilder::new()
+            .set_hash(H256::repeat_byte(0x2))
+            .build();
+        let mut tx2 = tx1.clone();
+        tx2.set_input(vec![], H256::repeat_byte(0x3));
+        tx2.common_data.fee.max_fee_per_gas = tx1.common_data.fee.max_fee_per_gas + 1;
+
+        pool.add(tx1.clone(), Nonce(0)).expect("failed adding tx");
+        pool.add(other.clone(), Nonce(0)).expect("failed adding tx");
+        let added = pool
+            .add(tx2.clone(), Nonce(0))
+            .expect("failed replacing tx");
+
+        assert_eq!(Some(tx1.hash()), added.replaced);
+        assert_eq!(
+            vec![tx2.hash(), other.hash()],
+            pool.take_all()
+                .iter()
+                .map(|tx| tx.hash())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_add_rejects_known_or_underpriced_tx() {
+        let mut pool = TxPool::default();
+        let tx1 = testing::TransactionBuilder::new()
+            .set_hash(H256::repeat_byte(0x1))
+            .build();
+        let mut tx2 = tx1.clone();
+        tx2.set_input(vec![], H256::repeat_byte(0x2));
+        tx2.common_data.fee.max_fee_per_gas = U256::one();
+
+        pool.add(tx1.clone(), Nonce(0)).expect("failed adding tx");
+
+        assert_eq!(
+            Err(String::from("replacement transaction underpriced")),
+            pool.add(tx2, Nonce(0))
+        );
+        assert_eq!(
+            Err(String::from("transaction already known")),
+            pool.add(tx1, Nonce(0))
+        );
+        assert_eq!(1, pool.len());
+    }
+
+    #[test]
+    fn test_add_rejects_tx_with_nonce_too_low() {
+        let mut pool = TxPool::default();
+        let private_key = K256PrivateKey::from_bytes(H256::random()).unwrap();
+
+        let result = pool.add(build_tx(&private_key, 0, 0x1), Nonce(1));
+
+        assert!(result.unwrap_err().starts_with("nonce too low"));
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    fn test_add_queues_tx_with_nonce_gap_until_gap_is_filled() {
+        let mut pool = TxPool::default();
+        let private_key = K256PrivateKey::from_bytes(H256::random()).unwrap();
+        let tx0 = build_tx(&private_key, 0, 0x1);
+        let tx1 = build_tx(&private_key, 1, 0x2);
+        let tx2 = build_tx(&private_key, 2, 0x3);
+
+        assert!(pool.add(tx2.clone(), Nonce(0)).unwrap().queued);
+        assert!(pool.add(tx1.clone(), Nonce(0)).unwrap().queued);
+        assert!(pool.is_empty());
+        assert_eq!(2, pool.queued_len());
+        assert!(pool.get(&tx1.hash()).is_some());
+
+        let added = pool.add(tx0.clone(), Nonce(0)).unwrap();
+
+        assert!(!added.queued);
+        assert_eq!(vec![tx1.hash(), tx2.hash()], added.promoted);
+        assert_eq!(0, pool.queued_len());
+        assert_eq!(
+            vec![tx0.hash(), tx1.hash(), tx2.hash()],
+            pool.take_all()
+                .iter()
+                .map(|tx| tx.hash())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_promote_moves_queued_txs_once_account_nonce_catches_up() {
+        let mut pool = TxPool::default();
+        let private_key = K256PrivateKey::from_bytes(H256::random()).unwrap();
+        let tx = build_tx(&private_key, 1, 0x1);
+
+        assert!(pool.add(tx.clone(), Nonce(0)).unwrap().queued);
+        assert!(pool.promote(tx.initiator_account(), Nonce(0)).is_empty());
+
+        assert_eq!(
+            vec![tx.hash()],
+            pool.promote(tx.initiator_account(), Nonce(1))
+        );
+        assert_eq!(1, pool.len());
+    }
+
+    #[test]
+    fn test_remove_drops_tx() {
+        let mut pool = TxPool::default();
+        let tx = testing::TransactionBuilder::new()
+            .set_hash(H256::repeat_byte(0x1))
+            .build();
+        pool.add(tx.clone(), Nonce(0)).expect("failed adding tx");
+
+        assert_eq!(Some(tx.hash()), pool.remove(&tx.hash()).map(|tx| tx.hash()));
+        assert!(pool.remove(&tx.hash()).is_none());
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    fn test_remove_queues_following_pending_txs() {
+        let mut pool = TxPool::default();
+        let private_key = K256PrivateKey::from_bytes(H256::random()).unwrap();
+        let tx0 = build_tx(&private_key, 0, 0x1);
+        let tx1 = build_tx(&private_key, 1, 0x2);
+        pool.add(tx0.clone(), Nonce(0)).expect("failed adding tx");
+        pool.add(tx1.clone(), Nonce(0)).expect("failed adding tx");
+        assert_eq!(2, pool.len());
+
+        pool.remove(&tx0.hash()).expect("tx exists");
+
+        assert!(pool.is_empty());
+        assert_eq!(1, pool.queued_len());
+        assert!(pool.get(&tx1.hash()).is_some());
+    }
+}
@@ -0,0 +1,219 @@
+//! Serializable state of the node, used to persist it across restarts.
+use multivm::interface::{ExecutionResult, VmExecutionResultAndLogs, VmRevertReason};
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{web3::Bytes, H256};
+use zksync_types::{
+    api::{Block, DebugCall, TransactionReceipt, TransactionVariant},
+    l2::L2Tx,
+    StorageKey, StorageValue,
+};
+
+use crate::{
+    fork::ForkSource,
+    node::{InMemoryNodeInner, TransactionResult, TxExecutionInfo},
+};
+
+/// Version of the [SerializableState] format, bumped on every incompatible change.
+pub const STATE_VERSION: u32 = 2;
+
+/// The state of the node that is written by `anvil_dumpState` and read back by `anvil_loadState`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerializableState {
+    pub version: u32,
+    pub current_timestamp: u64,
+    pub current_batch: u32,
+    pub current_miniblock: u64,
+    pub current_miniblock_hash: H256,
+    pub blocks: Vec<Block<TransactionVariant>>,
+    pub transactions: Vec<SerializableTransaction>,
+    /// The local storage, values that were read from a fork are fetched again when needed.
+    pub storage: Vec<(StorageKey, StorageValue)>,
+    pub factory_deps: Vec<(H256, Bytes)>,
+}
+
+/// A sealed transaction along with its execution outcome, receipt and call trace.
+///
+/// The logs and statistics of the VM execution are not persisted, as they are only needed while sealing the
+/// transaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerializableTransaction {
+    pub tx: L2Tx,
+    pub batch_number: u32,
+    pub miniblock_number: u64,
+    pub result: SerializableExecutionResult,
+    pub receipt: TransactionReceipt,
+    pub debug: DebugCall,
+}
+
+/// The outcome of the VM execution of a sealed transaction.
+///
+/// Halted transactions are never sealed, so only successful and reverted executions are distinguished.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum SerializableExecutionResult {
+    Success { output: Bytes },
+    Revert { message: String, data: Bytes },
+}
+
+impl From<&ExecutionResult> for SerializableExecutionResult {
+    fn from(result: &ExecutionResult) -> Self {
+        match result {
+            ExecutionResult::Success { output } => Self::Success {
+                output: Bytes(output.clone()),
+            },
+            ExecutionResult::Revert { output } => Self::Revert {
+                message: output.to_user_friendly_string(),
+                data: Bytes(output.encoded_data()),
+            },
+            ExecutionResult::Halt { reason } => Self::Revert {
+                message: reason.to_string(),
+                data: Bytes(vec![]),
+            },
+        }
+    }
+}
+
+impl From<SerializableExecutionResult> for ExecutionResult {
+    fn from(result: SerializableExecutionResult) -> Self {
+        match result {
+            SerializableExecutionResult::Success { output } => Self::Success { output: output.0 },
+            SerializableExecutionResult::Revert { message, data } => Self::Revert {
+                output: VmRevertReason::General {
+                    msg: message,
+                    data: data.0,
+                },
+            },
+        }
+    }
+}
+
+impl From<&TransactionResult> for SerializableTransaction {
+    fn from(result: &TransactionResult) -> Self {
+        Self {
+            tx: result.info.tx.clone(),
+            batch_number: result.info.batch_number,
+            miniblock_number: result.info.miniblock_number,
+            result: SerializableExecutionResult::from(&result.info.result.result),
+            receipt: result.receipt.clone(),
+            debug: result.debug.clone(),
+        }
+    }
+}
+
+impl From<SerializableTransaction> for TransactionResult {
+    fn from(tx: SerializableTransaction) -> Self {
+        Self {
+            info: TxExecutionInfo {
+                tx: tx.tx,
+                batch_number: tx.batch_number,
+                miniblock_number: tx.miniblock_number,
+                result: VmExecutionResultAndLogs {
+                    result: tx.result.into(),
+                    logs: Default::default(),
+                    statistics: Default::default(),
+                    refunds: Default::default(),
+                },
+            },
+            receipt: tx.receipt,
+            debug: tx.debug,
+        }
+    }
+}
+
+impl<S: std::fmt::Debug + ForkSource> InMemoryNodeInner<S> {
+    /// Returns the current state of the node in a serializable format.
+    pub fn dump_state(&self) -> Result<SerializableState, String> {
+        let storage = self
+            .fork_storage
+            .inner
+            .read()
+            .map_err(|err| format!("failed acquiring read lock on storage: {:?}", err))?;
+
+        let mut blocks = self.blocks.values().cloned().collect::<Vec<_>>();
+        blocks.sort_by_key(|block| block.number);
+        let mut transactions = self
+            .tx_results
+            .values()
+            .map(SerializableTransaction::from)
+            .collect::<Vec<_>>();
+        transactions.sort_by_key(|tx| (tx.miniblock_number, tx.receipt.transaction_index));
+
+        Ok(SerializableState {
+            version: STATE_VERSION,
+            current_timestamp: self.current_timestamp,
+            current_batch: self.current_batch,
+            current_miniblock: self.current_miniblock,
+            current_miniblock_hash: self.current_miniblock_hash,
+            blocks,
+            transactions,
+            storage: storage
+                .raw_storage
+                .state
+                .iter()
+                .map(|(key, value)| (*key, *value))
+                .collect(),
+            factory_deps: storage
+                .raw_storage
+                .factory_deps
+                .iter()
+                .map(|(hash, bytecode)| (*hash, Bytes(bytecode.clone())))
+                .collect(),
+        })
+    }
+
+    /// Replaces the state of the node with a previously dumped [SerializableState].
+    ///
+    /// The historical states of the previous blocks are not part of the dump, so they are discarded. The pending
+    /// transactions were accepted against the replaced state, so they are dropped, and the filters are reset.
+    pub fn load_state(&mut self, state: SerializableState) -> Result<(), String> {
+        if state.version != STATE_VERSION {
+            return Err(format!(
+                "unsupported state version {}, expected {}",
+                state.version, STATE_VERSION
+            ));
+        }
+
+        for tx in self.pool.clear() {
+            self.filters.notify_dropped_transaction(tx.hash());
+        }
+        self.filters = Default::default();
+
+        let mut storage = self
+            .fork_storage
+            .inner
+            .write()
+            .map_err(|err| format!("failed acquiring write lock on storage: {:?}", err))?;
+
+        self.current_timestamp = state.current_timestamp;
+        self.current_batch = state.current_batch;
+        self.current_miniblock = state.current_miniblock;
+        self.current_miniblock_hash = state.current_miniblock_hash;
+        self.block_hashes = state
+            .blocks
+            .iter()
+            .map(|block| (block.number.as_u64(), block.hash))
+            .collect();
+        self.blocks = state
+            .blocks
+            .into_iter()
+            .map(|block| (block.hash, block))
+            .collect();
+        self.tx_results = state
+            .transactions
+            .into_iter()
+            .map(|tx| (tx.tx.hash(), TransactionResult::from(tx)))
+            .collect();
+        self.previous_states.clear();
+        storage.journal.clear();
+        storage.raw_storage.state = state.storage.into_iter().collect();
+        storage.raw_storage.factory_deps = state
+            .factory_deps
+            .into_iter()
+            .map(|(hash, bytecode)| (hash, bytecode.0))
+            .collect();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+
+use zksync_basic_types::{Address, U64};
+use zksync_types::{api::Transaction, l2::L2Tx};
+use zksync_web3_decl::error::Web3Error;
+
+use crate::{
+    fork::ForkSource,
+    namespaces::{RpcResult, TxpoolContent, TxpoolNamespaceT, TxpoolStatus},
+    node::{pool::to_api_transaction, InMemoryNode},
+    utils::{into_jsrpc_error, IntoBoxedFuture},
+};
+
+impl<S: ForkSource + std::fmt::Debug + Clone + Send + Sync + 'static> TxpoolNamespaceT
+    for InMemoryNode<S>
+{
+    fn txpool_status(&self) -> RpcResult<TxpoolStatus> {
+        self.get_inner()
+            .read()
+            .map(|reader| TxpoolStatus {
+                pending: U64::from(reader.pool.len()),
+                queued: U64::from(reader.pool.queued_len()),
+            })
+            .map_err(|_| {
+                into_jsrpc_error(Web3Error::InternalError(anyhow::Error::msg(
+                    "Failed to acquire read lock for the transaction pool.",
+                )))
+            })
+            .into_boxed_future()
+    }
+
+    fn txpool_content(&self) -> RpcResult<TxpoolContent<Transaction>> {
+        self.get_inner()
+            .read()
+            .map(|reader| TxpoolContent {
+                pending: group_by_sender(reader.pool.pending(), to_api_transaction),
+                queued: group_by_sender(reader.pool.queued(), to_api_transaction),
+            })
+            .map_err(|_| {
+                into_jsrpc_error(Web3Error::InternalError(anyhow::Error::msg(
+                    "Failed to acquire read lock for the transaction pool.",
+                )))
+            })
+            .into_boxed_future()
+    }
+
+    fn txpool_inspect(&self) -> RpcResult<TxpoolContent<String>> {
+        self.get_inner()
+            .read()
+            .map(|reader| TxpoolContent {
+                pending: group_by_sender(reader.pool.pending(), inspect_summary),
+                queued: group_by_sender(reader.pool.queued(), inspect_summary),
+            })
+            .map_err(|_| {
+                into_jsrpc_error(Web3Error::InternalError(anyhow::Error::msg(
+                    "Failed to acquire read lock for the transaction pool.",
+                )))
+            })
+            .into_boxed_future()
+    }
+}
+
+/// Groups the transactions by sender and nonce, mapping each one with `f`.
+fn group_by_sender<'a, T>(
+    txs: impl Iterator<Item = &'a L2Tx>,
+    f: impl Fn(&L2Tx) -> T,
+) -> BTreeMap<Address, BTreeMap<String, T>> {
+    let mut grouped: BTreeMap<Address, BTreeMap<String, T>> = BTreeMap::new();
+    for tx in txs {
+        grouped
+            .entry(tx.initiator_account())
+            .or_default()
+            .insert(tx.common_data.nonce.0.to_string(), f(tx));
+    }
+    grouped
+}
+
+/// Summarizes a transaction in the same format as geth, e.g. `0x...: 1 wei + 21000 gas × 250000000 wei`.
+fn inspect_summary(tx: &L2Tx) -> String {
+    format!(
+        "{:?}: {} wei + {} gas × {} wei",
+        tx.recipient_account(),
+        tx.execute.value,
+        tx.common_data.fee.gas_limit,
+        tx.common_data.fee.max_fee_per_gas
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_basic_types::{Nonce, H256};
+    use zksync_types::K256PrivateKey;
+
+    use super::*;
+    use crate::{http_fork_source::HttpForkSource, testing};
+
+    #[tokio::test]
+    async fn test_txpool_groups_pending_and_queued_txs_by_sender_and_nonce() {
+        let node = InMemoryNode::<HttpForkSource>::default();
+        node.set_automine(false).expect("set_automine");
+        let private_key = K256PrivateKey::from_bytes(H256::random()).unwrap();
+        let txs = [0, 2].map(|nonce| {
+            testing::TransactionBuilder::new()
+                .set_from_account_private_key(private_key.clone())
+                .set_nonce(Nonce(nonce))
+                .set_hash(H256::repeat_byte(nonce as u8 + 1))
+                .build()
+        });
+        let sender = txs[0].initiator_account();
+        node.set_rich_account(sender);
+        for tx in &txs {
+            node.submit_l2_tx(tx.clone()).expect("failed submitting tx");
+        }
+
+        let status = node.txpool_status().await.expect("txpool_status");
+        assert_eq!(U64::from(1), status.pending);
+        assert_eq!(U64::from(1), status.queued);
+
+        let content = node.txpool_content().await.expect("txpool_content");
+        assert_eq!(txs[0].hash(), content.pending[&sender]["0"].hash);
+        assert_eq!(txs[1].hash(), content.queued[&sender]["2"].hash);
+
+        let inspect = node.txpool_inspect().await.expect("txpool_inspect");
+        assert_eq!(
+            format!(
+                "{:?}: 1 wei + {} gas × {} wei",
+                txs[0].recipient_account(),
+                txs[0].common_data.fee.gas_limit,
+                txs[0].common_data.fee.max_fee_per_gas
+            ),
+            inspect.pending[&sender]["0"]
+        );
+    }
+}
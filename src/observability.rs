@@ -1,5 +1,8 @@
 use core::fmt;
-use std::{fs::File, sync::Mutex};
+use std::{
+    fs::File,
+    sync::{Arc, Mutex},
+};
 
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
@@ -48,6 +51,8 @@ impl From<LogLevel> for LevelFilter {
 pub struct Observability {
     binary_names: Vec<String>,
     reload_handle: Option<reload::Handle<EnvFilter, Registry>>,
+    /// A handle to the log file, used to flush it on shutdown.
+    log_file: Option<Arc<File>>,
 }
 
 impl Observability {
@@ -65,6 +70,8 @@ impl Observability {
         let filter = Self::parse_filter(&joined_filter)?;
         let (filter, reload_handle) = reload::Layer::new(filter);
 
+        let log_file_handle = log_file.try_clone()?;
+
         let timer_format =
             time::format_description::parse("[hour]:[minute]:[second]").expect("Cataplum");
         let time_offset = time::UtcOffset::current_local_offset().unwrap_or(time::UtcOffset::UTC);
@@ -96,6 +103,7 @@ impl Observability {
         Ok(Self {
             binary_names,
             reload_handle: Some(reload_handle),
+            log_file: Some(Arc::new(log_file_handle)),
         })
     }
 
@@ -130,6 +138,15 @@ impl Observability {
         Ok(())
     }
 
+    /// Flushes all the logs written so far to the log file.
+    pub fn flush(&self) -> Result<(), anyhow::Error> {
+        if let Some(log_file) = &self.log_file {
+            log_file.sync_all()?;
+        }
+
+        Ok(())
+    }
+
     /// Parses a directive and builds an [EnvFilter] from it.
     /// Example:
     ///     * "my_crate=debug"
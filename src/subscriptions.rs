@@ -0,0 +1,288 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use jsonrpc_pubsub::{
+    typed::{Sink, Subscriber},
+    SubscriptionId,
+};
+use zksync_basic_types::H256;
+use zksync_types::api::{Block, Log, TransactionVariant};
+use zksync_web3_decl::types::{BlockHeader, PubSubFilter, PubSubResult};
+
+/// Specifies what a subscription is notified of
+#[derive(Debug, Clone)]
+pub enum SubscriptionKind {
+    /// Headers of newly produced blocks
+    NewHeads,
+    /// Transaction logs matching the filter
+    Logs(PubSubFilter),
+    /// Hashes of newly submitted transactions
+    NewPendingTransactions,
+}
+
+impl SubscriptionKind {
+    /// Parses the subscription type and its optional params, as passed to `eth_subscribe`.
+    pub fn new(sub_type: &str, params: Option<PubSubFilter>) -> Result<Self, String> {
+        match sub_type {
+            "newHeads" => Ok(Self::NewHeads),
+            "logs" => Ok(Self::Logs(params.unwrap_or(PubSubFilter {
+                address: None,
+                topics: None,
+            }))),
+            "newPendingTransactions" => Ok(Self::NewPendingTransactions),
+            _ => Err(format!("unsupported subscription type '{}'", sub_type)),
+        }
+    }
+}
+
+struct Subscription {
+    kind: SubscriptionKind,
+    sink: Sink<PubSubResult>,
+}
+
+#[derive(Default)]
+struct SubscriptionsInner {
+    id_counter: u64,
+    subscriptions: HashMap<SubscriptionId, Subscription>,
+}
+
+/// Keeps track of the `eth_subscribe` subscriptions, which are notified at the same points as the
+/// [EthFilters](crate::filters::EthFilters). Clones share the same subscriptions, as they are bound to
+/// connections and not to the state of the node.
+#[derive(Clone, Default)]
+pub struct EthSubscriptions {
+    inner: Arc<Mutex<SubscriptionsInner>>,
+}
+
+impl std::fmt::Debug for EthSubscriptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EthSubscriptions").finish_non_exhaustive()
+    }
+}
+
+impl EthSubscriptions {
+    /// Assigns an id to the subscriber and starts notifying it. Returns the subscription id.
+    pub fn add(
+        &self,
+        kind: SubscriptionKind,
+        subscriber: Subscriber<PubSubResult>,
+    ) -> Result<SubscriptionId, String> {
+        let mut inner = self
+            .inner
+            .lock()
+            .map_err(|err| format!("failed acquiring lock: {:?}", err))?;
+        inner.id_counter = inner.id_counter.checked_add(1).ok_or("overflow")?;
+        let id = SubscriptionId::String(format!("{:#x}", inner.id_counter));
+        let sink = subscriber
+            .assign_id(id.clone())
+            .map_err(|_| "subscriber is already closed".to_string())?;
+
+        tracing::info!("created {:?} subscription '{:?}'", kind, id);
+        inner
+            .subscriptions
+            .insert(id.clone(), Subscription { kind, sink });
+        Ok(id)
+    }
+
+    /// Removes the subscription with the given id. Returns true if the subscription existed, false otherwise.
+    pub fn remove(&self, id: &SubscriptionId) -> bool {
+        tracing::info!("removing subscription '{:?}'", id);
+        self.inner
+            .lock()
+            .map(|mut inner| inner.subscriptions.remove(id).is_some())
+            .unwrap_or_default()
+    }
+
+    /// Notify available subscriptions of a newly produced block
+    pub fn notify_new_block(&self, block: &Block<TransactionVariant>) {
+        self.notify(|kind| match kind {
+            SubscriptionKind::NewHeads => Some(PubSubResult::Header(block_header(block))),
+            _ => None,
+        })
+    }
+
+    /// Notify available subscriptions of a new pending transaction
+    pub fn notify_new_pending_transaction(&self, hash: H256) {
+        self.notify(|kind| match kind {
+            SubscriptionKind::NewPendingTransactions => Some(PubSubResult::TxHash(hash)),
+            _ => None,
+        })
+    }
+
+    /// Notify available subscriptions of a new transaction log
+    pub fn notify_log(&self, log: &Log) {
+        self.notify(|kind| match kind {
+            SubscriptionKind::Logs(filter) if filter.matches(log) => {
+                Some(PubSubResult::Log(log.clone()))
+            }
+            _ => None,
+        })
+    }
+
+    /// Notify available subscriptions that a transaction log was removed by a chain rollback
+    pub fn notify_removed_log(&self, log: &Log) {
+        self.notify_log(&Log {
+            removed: Some(true),
+            ..log.clone()
+        })
+    }
+
+    /// Sends the result built for each subscription, and drops the subscriptions whose connection was closed.
+    fn notify(&self, result: impl Fn(&SubscriptionKind) -> Option<PubSubResult>) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        inner.subscriptions.retain(|id, subscription| {
+            let Some(result) = result(&subscription.kind) else {
+                return true;
+            };
+            match subscription.sink.notify(Ok(result)) {
+                Ok(()) => true,
+                Err(err) => {
+                    tracing::debug!("dropping subscription '{:?}': {:?}", id, err);
+                    false
+                }
+            }
+        });
+    }
+}
+
+/// Builds the header sent to `newHeads` subscriptions.
+fn block_header(block: &Block<TransactionVariant>) -> BlockHeader {
+    BlockHeader {
+        hash: Some(block.hash),
+        parent_hash: block.parent_hash,
+        uncles_hash: block.uncles_hash,
+        author: block.author,
+        state_root: block.state_root,
+        transactions_root: block.transactions_root,
+        receipts_root: block.receipts_root,
+        number: Some(block.number),
+        gas_used: block.gas_used,
+        gas_limit: block.gas_limit,
+        base_fee_per_gas: Some(block.base_fee_per_gas),
+        extra_data: block.extra_data.clone(),
+        logs_bloom: block.logs_bloom,
+        timestamp: block.timestamp,
+        difficulty: block.difficulty,
+        mix_hash: Some(block.mix_hash),
+        nonce: Some(block.nonce),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use zksync_basic_types::{H160, U64};
+    use zksync_web3_decl::types::ValueOrArray;
+
+    use crate::testing::LogBuilder;
+
+    use super::*;
+
+    fn subscribe(
+        subscriptions: &EthSubscriptions,
+        kind: SubscriptionKind,
+    ) -> (
+        SubscriptionId,
+        futures::channel::mpsc::UnboundedReceiver<String>,
+    ) {
+        // the id receiver must be alive while the id is assigned
+        let (subscriber, _id_receiver, notifications) = Subscriber::new_test("eth_subscription");
+        let id = subscriptions
+            .add(kind, subscriber)
+            .expect("failed adding subscription");
+        (id, notifications)
+    }
+
+    #[test]
+    fn test_subscription_kind_rejects_unknown_types() {
+        assert!(matches!(
+            SubscriptionKind::new("newHeads", None),
+            Ok(SubscriptionKind::NewHeads)
+        ));
+        assert!(matches!(
+            SubscriptionKind::new("logs", None),
+            Ok(SubscriptionKind::Logs(_))
+        ));
+        assert!(SubscriptionKind::new("syncing", None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_notify_new_block_is_sent_to_new_heads_subscriptions_only() {
+        let subscriptions = EthSubscriptions::default();
+        let (_, mut heads) = subscribe(&subscriptions, SubscriptionKind::NewHeads);
+        let (_, mut pending) = subscribe(&subscriptions, SubscriptionKind::NewPendingTransactions);
+
+        let block = Block::<TransactionVariant> {
+            hash: H256::repeat_byte(0x1),
+            number: U64::from(1),
+            ..Default::default()
+        };
+        subscriptions.notify_new_block(&block);
+        drop(subscriptions);
+
+        let notification: serde_json::Value =
+            serde_json::from_str(&heads.next().await.expect("missing notification")).unwrap();
+        assert_eq!(
+            serde_json::json!(format!("{:#x}", block.hash)),
+            notification["params"]["result"]["hash"]
+        );
+        assert_eq!(None, pending.next().await);
+    }
+
+    #[tokio::test]
+    async fn test_notify_log_is_sent_to_matching_subscriptions() {
+        let subscriptions = EthSubscriptions::default();
+        let match_address = H160::repeat_byte(0x1);
+        let (_, mut matching) = subscribe(
+            &subscriptions,
+            SubscriptionKind::Logs(PubSubFilter {
+                address: Some(ValueOrArray(vec![match_address])),
+                topics: None,
+            }),
+        );
+        let (_, mut other) = subscribe(
+            &subscriptions,
+            SubscriptionKind::Logs(PubSubFilter {
+                address: Some(ValueOrArray(vec![H160::repeat_byte(0x2)])),
+                topics: None,
+            }),
+        );
+
+        let log = LogBuilder::new()
+            .set_address(match_address)
+            .set_block(U64::from(1))
+            .build();
+        subscriptions.notify_log(&log);
+        drop(subscriptions);
+
+        assert!(matching.next().await.is_some());
+        assert_eq!(None, other.next().await);
+    }
+
+    #[tokio::test]
+    async fn test_removed_subscriptions_are_not_notified() {
+        let subscriptions = EthSubscriptions::default();
+        let (id, mut pending) = subscribe(&subscriptions, SubscriptionKind::NewPendingTransactions);
+
+        assert!(subscriptions.remove(&id));
+        assert!(!subscriptions.remove(&id));
+        subscriptions.notify_new_pending_transaction(H256::repeat_byte(0x1));
+
+        assert_eq!(None, pending.next().await);
+    }
+
+    #[tokio::test]
+    async fn test_closed_subscriptions_are_dropped() {
+        let subscriptions = EthSubscriptions::default();
+        let (id, pending) = subscribe(&subscriptions, SubscriptionKind::NewPendingTransactions);
+        drop(pending);
+
+        subscriptions.notify_new_pending_transaction(H256::repeat_byte(0x1));
+
+        assert!(!subscriptions.remove(&id));
+    }
+}
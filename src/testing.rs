@@ -392,6 +392,7 @@ impl RawTransactionsResponseBuilder {
 pub struct TransactionBuilder {
     tx_hash: H256,
     from_account_private_key: K256PrivateKey,
+    nonce: Nonce,
     gas_limit: U256,
     max_fee_per_gas: U256,
     max_priority_fee_per_gas: U256,
@@ -402,6 +403,7 @@ impl Default for TransactionBuilder {
         Self {
             tx_hash: H256::repeat_byte(0x01),
             from_account_private_key: K256PrivateKey::from_bytes(H256::random()).unwrap(),
+            nonce: Nonce(0),
             gas_limit: U256::from(4_000_000),
             max_fee_per_gas: U256::from(50_000_000),
             max_priority_fee_per_gas: U256::from(50_000_000),
@@ -419,6 +421,16 @@ impl TransactionBuilder {
         self
     }
 
+    pub fn set_from_account_private_key(&mut self, private_key: K256PrivateKey) -> &mut Self {
+        self.from_account_private_key = private_key;
+        self
+    }
+
+    pub fn set_nonce(&mut self, nonce: Nonce) -> &mut Self {
+        self.nonce = nonce;
+        self
+    }
+
     pub fn set_gas_limit(&mut self, gas_limit: U256) -> &mut Self {
         self.gas_limit = gas_limit;
         self
@@ -438,7 +450,7 @@ impl TransactionBuilder {
         let mut tx = L2Tx::new_signed(
             Address::random(),
             vec![],
-            Nonce(0),
+            self.nonce,
             Fee {
                 gas_limit: self.gas_limit,
                 max_fee_per_gas: self.max_fee_per_gas,
@@ -1040,10 +1052,12 @@ mod test {
                         input_l2_block,
                         input_timestamp,
                     ))
-                },
+                }
+                .into(),
                 factory_deps: hashmap! {
                     H256::repeat_byte(0x1) => input_bytecode.clone(),
-                },
+                }
+                .into(),
             },
         };
 
@@ -132,6 +132,9 @@ pub fn mine_empty_blocks<S: std::fmt::Debug + ForkSource>(
             (modified_keys, bytecodes, block_ctx)
         };
 
+        // archive the state of the current block before it's modified by the new one
+        node.archive_state().map_err(anyhow::Error::msg)?;
+
         for (key, value) in keys.iter() {
             node.fork_storage.set_value(*key, *value);
         }
@@ -155,14 +158,19 @@ pub fn mine_empty_blocks<S: std::fmt::Debug + ForkSource>(
             block_ctx.timestamp,
             block_ctx.batch,
             None,
+            node.config.block_gas_limit,
         );
 
-        node.block_hashes.insert(block.number.as_u64(), block.hash);
-        node.blocks.insert(block.hash, block);
+        let block_hash = block.hash;
+        node.filters.notify_new_block(block_hash);
+        node.subscriptions.notify_new_block(&block);
+        node.block_hashes.insert(block.number.as_u64(), block_hash);
+        node.blocks.insert(block_hash, block);
 
         // leave node state ready for next interaction
         node.current_batch = block_ctx.batch;
         node.current_miniblock = block_ctx.miniblock;
+        node.current_miniblock_hash = block_hash;
         node.current_timestamp = block_ctx.timestamp;
     }
 